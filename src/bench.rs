@@ -0,0 +1,220 @@
+use crate::{AocError, Day};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt, fs, hint, io,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+pub const DEFAULT_ITERATIONS: usize = 10;
+
+#[derive(Debug, thiserror::Error)]
+pub enum BaselineError {
+    #[error("could not access baseline: {0}")]
+    Io(#[from] io::Error),
+    #[error("could not parse baseline: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// The separately timed steps of a solution.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Stage {
+    Parse,
+    Part1,
+    Part2,
+}
+
+impl Stage {
+    pub fn of_part(part: u8) -> Stage {
+        if part == 1 {
+            Stage::Part1
+        } else {
+            Stage::Part2
+        }
+    }
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Stage::Parse => "parse",
+            Stage::Part1 => "part 1",
+            Stage::Part2 => "part 2",
+        })
+    }
+}
+
+/// The fastest, median and slowest of several runs of one stage, in
+/// nanoseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Timing {
+    pub day: u8,
+    pub stage: Stage,
+    pub iterations: usize,
+    pub min_ns: u64,
+    pub median_ns: u64,
+    pub max_ns: u64,
+}
+
+impl Timing {
+    /// Summarises the time taken by each run.
+    ///
+    /// ```
+    /// # use advent_of_code_2022::bench::{Stage, Timing};
+    /// # use std::time::Duration;
+    /// let samples = [5, 1, 3].map(Duration::from_nanos);
+    /// let timing = Timing::from_samples(4, Stage::Parse, &samples);
+    /// assert_eq!(
+    ///     (timing.min_ns, timing.median_ns, timing.max_ns),
+    ///     (1, 3, 5)
+    /// );
+    /// ```
+    pub fn from_samples(day: u8, stage: Stage, samples: &[Duration]) -> Timing {
+        let mut sorted: Vec<u64> = samples.iter().copied().map(nanos).collect();
+        sorted.sort_unstable();
+        Timing {
+            day,
+            stage,
+            iterations: sorted.len(),
+            min_ns: sorted.first().copied().unwrap_or_default(),
+            median_ns: sorted
+                .get(sorted.len() / 2)
+                .copied()
+                .unwrap_or_default(),
+            max_ns: sorted.last().copied().unwrap_or_default(),
+        }
+    }
+
+    /// Runs `stage` `iterations` times, stopping at the first error.
+    pub fn measure<T>(
+        day: u8,
+        stage: Stage,
+        iterations: usize,
+        mut run: impl FnMut() -> Result<T, AocError>,
+    ) -> Result<Timing, AocError> {
+        let mut samples = Vec::with_capacity(iterations);
+        for _ in 0..iterations {
+            let start = Instant::now();
+            hint::black_box(run()?);
+            samples.push(start.elapsed());
+        }
+        Ok(Timing::from_samples(day, stage, &samples))
+    }
+
+    /// How much the median changed since `baseline`, as a percentage.
+    /// Negative means faster.
+    ///
+    /// ```
+    /// # use advent_of_code_2022::bench::{Stage, Timing};
+    /// # use std::time::Duration;
+    /// let old = Timing::from_samples(8, Stage::Part2, &[Duration::from_micros(8)]);
+    /// let new = Timing::from_samples(8, Stage::Part2, &[Duration::from_micros(4)]);
+    /// assert_eq!(new.change(&old), -50.0);
+    /// ```
+    pub fn change(&self, baseline: &Timing) -> f64 {
+        let seconds = |nanos| Duration::from_nanos(nanos).as_secs_f64();
+        let (new, old) = (seconds(self.median_ns), seconds(baseline.median_ns));
+        if old == 0.0 {
+            return 0.0;
+        }
+        (new - old) / old * 100.0
+    }
+}
+
+impl fmt::Display for Timing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show = |nanos| format!("{:.1?}", Duration::from_nanos(nanos));
+        write!(
+            f,
+            "Day {:>2} {:<6}  min {:>9}  median {:>9}  max {:>9}",
+            self.day,
+            self.stage,
+            show(self.min_ns),
+            show(self.median_ns),
+            show(self.max_ns)
+        )
+    }
+}
+
+/// A duration in whole nanoseconds, saturating after about 584 years.
+pub fn nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
+/// The timings of a benchmark run, saved as JSON to compare later runs with.
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Report(pub Vec<Timing>);
+
+impl Report {
+    pub fn load(path: impl AsRef<Path>) -> Result<Report, BaselineError> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), BaselineError> {
+        fs::write(path, serde_json::to_string_pretty(self)? + "\n")?;
+        Ok(())
+    }
+
+    pub fn get(&self, day: u8, stage: Stage) -> Option<&Timing> {
+        self.0.iter().find(|x| x.day == day && x.stage == stage)
+    }
+}
+
+/// Times parsing `input` and then each of the selected parts on the parsed
+/// solution, so that parsing is only counted once.
+pub fn bench_day(
+    day: &Day,
+    input: &str,
+    parts: &[u8],
+    iterations: usize,
+) -> Result<Vec<Timing>, AocError> {
+    let mut timings = vec![Timing::measure(
+        day.number,
+        Stage::Parse,
+        iterations,
+        || (day.parse)(input),
+    )?];
+    let solution = (day.parse)(input)?;
+    for &part in parts {
+        let stage = Stage::of_part(part);
+        timings.push(Timing::measure(day.number, stage, iterations, || {
+            match stage {
+                Stage::Part1 => solution.part1(),
+                _ => solution.part2(),
+            }
+        })?);
+    }
+    Ok(timings)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::bench::{self, Report, Stage};
+    use std::env;
+
+    #[test]
+    fn can_bench_every_stage() {
+        let day = crate::day(6).unwrap();
+        let timings =
+            bench::bench_day(day, "abcdefghijklmnopq", &[1, 2], 3).unwrap();
+        let stages: Vec<_> = timings.iter().map(|x| x.stage).collect();
+        assert_eq!(stages, vec![Stage::Parse, Stage::Part1, Stage::Part2]);
+        assert!(timings.iter().all(|x| x.iterations == 3));
+        assert!(timings.iter().all(|x| x.min_ns <= x.max_ns));
+    }
+
+    #[test]
+    fn can_save_and_load_report() {
+        let day = crate::day(1).unwrap();
+        let report = Report(bench::bench_day(day, "1\n\n2", &[1], 1).unwrap());
+        let path = env::temp_dir().join("aoc_2022_bench_report.json");
+        report.save(&path).unwrap();
+        let loaded = Report::load(&path).unwrap();
+        assert_eq!(loaded, report);
+        assert!(loaded.get(1, Stage::Parse).is_some());
+        assert!(loaded.get(1, Stage::Part2).is_none());
+    }
+}
@@ -0,0 +1,1153 @@
+use crate::bench::{self, Report};
+use crate::day10::{self, Breakpoint, Computer};
+use crate::day11::{self, Backend, Rules, Strictness};
+use crate::verify::{self, Answers};
+use crate::{day, day9, Answer, AocError, Day, DAYS};
+use itertools::Itertools;
+use serde::Serialize;
+use std::{
+    fs,
+    io::{self, Read},
+    path::PathBuf,
+    thread,
+    time::{Duration, Instant},
+};
+
+pub const USAGE: &str = "\
+usage: advent_of_code_2022 [run] [DAYS] [PART] [--input FILE] [--json]
+       advent_of_code_2022 verify [DAYS] [PART] [--answers FILE]
+       advent_of_code_2022 bench [DAYS] [PART] [--iterations N]
+                                 [--save FILE] [--baseline FILE]
+       advent_of_code_2022 rope [--input FILE] [--knots N] [--slack N]
+                                [--steps] [--frames DIR] [--delay MS]
+       advent_of_code_2022 trace [--input FILE] [--break WHEN]... [--step]
+       advent_of_code_2022 draw (TEXT | --image FILE)
+       advent_of_code_2022 monkeys [--input FILE] [--rounds N] [--relief N]
+                                   [--worry WORRY] [--top N]
+                                   [--after ROUNDS [--holdings]]
+                                   [--history ITEM] [--strict]
+
+DAYS   all (default), a day number such as 9, or an inclusive range such
+       as 3..7
+PART   1 or 2, both parts are run if omitted
+
+run      print the answers
+         --input FILE  input for a single day, or - to read it from stdin;
+                       defaults to dayN.txt in the current directory
+         --json        print one JSON object per line for each day and part,
+                       with its answer or error and how long it took
+verify   compare the answers for dayN.txt with the expected answers
+         --answers FILE  defaults to answers.toml
+bench    time parsing and each part on dayN.txt, reporting the fastest,
+         median and slowest run
+         --iterations N    runs of each step, defaults to 10
+         --save FILE       write the timings to FILE as JSON
+         --baseline FILE   compare medians with timings saved earlier
+rope     animate the day 9 rope in the terminal, finishing with the cells
+         its tail visited
+         --input FILE  moves to make, which may be diagonal such as UR,
+                       defaults to day9.txt
+         --knots N     knots in the rope, defaults to 10
+         --slack N     how far apart knots can be before they pull on each
+                       other, defaults to 1
+         --steps       draw every step rather than every move
+         --frames DIR  write each frame to a numbered file in DIR instead
+         --delay MS    time between frames, defaults to 100
+trace    print the day 10 computer's registers and instruction during every
+         cycle
+         --input FILE  program to run, defaults to day10.txt, which may use
+                       labels and comments
+         --break WHEN  only print cycles where WHEN holds, such as cycle=20
+                       or x=5
+         --step        stop at every breakpoint, or every cycle if there are
+                       none, and wait for a command: enter to run one cycle,
+                       c to continue to the next breakpoint, q to quit
+draw     print a day 10 program whose screen shows TEXT, up to 8 capital
+         letters from the puzzle font or spaces, such as ZGCJZJFL
+         --image FILE  draw a 40 by 6 picture of # and . instead, or read
+                       it from stdin with -
+monkeys  play day 11 with other rules and print the monkey business
+         --input FILE     notes on the monkeys, defaults to day11.txt
+         --rounds N       rounds to play, defaults to 10000
+         --relief N       divide worry levels by N after each inspection,
+                          defaults to 1
+         --worry WORRY    how worry levels are kept: modulo to keep them
+                          below the divisors' least common multiple, which
+                          must fit in 64 bits, checked to fail if they pass
+                          the largest 64-bit number, big for levels of any
+                          size, or residues to play each item on its own,
+                          skipping ahead when it repeats itself; modulo and
+                          residues need a relief of 1, and the default is
+                          modulo, or big with relief
+         --top N          multiply the inspections of the N busiest
+                          monkeys, defaults to 2
+         --after ROUNDS   print how many items each monkey has inspected
+                          after each of a list of rounds, such as 1,20,1000
+         --holdings       print the items each monkey holds instead
+         --history ITEM   print the monkeys that inspect the ITEMth
+                          starting item, counting from 0, in each round,
+                          with no relief
+         --strict         check that every line of the notes is labelled
+                          and worded as in the puzzle";
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum CliError {
+    #[error("unknown argument {0}")]
+    UnknownArgument(String),
+    #[error("{0} is not a day, use all, a number or a range like 3..7")]
+    InvalidDays(String),
+    #[error("day {0} has not been solved")]
+    UnknownDay(u8),
+    #[error("{0} is not a part, use 1 or 2")]
+    InvalidPart(String),
+    #[error("--input needs a file name or -")]
+    MissingInput,
+    #[error("--input can only be used with a single day")]
+    InputForManyDays,
+    #[error("{0} needs a file name")]
+    MissingFile(String),
+    #[error("draw needs TEXT or --image FILE")]
+    MissingImage,
+    #[error(
+        "{0} is not a way to keep worry levels, use modulo, checked, big or \
+         residues"
+    )]
+    InvalidWorry(String),
+    #[error("--history needs the number of an item, counting from 0")]
+    InvalidItem,
+    #[error("--history plays without relief and can't be used with {0}")]
+    NotWithHistory(String),
+    #[error("{0} needs a positive number")]
+    InvalidNumber(String),
+    #[error("{0} is not a breakpoint, use cycle=N or a register like x=N")]
+    InvalidBreakpoint(String),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Input {
+    Default,
+    Stdin,
+    File(PathBuf),
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Output {
+    #[default]
+    Text,
+    Json,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Selection {
+    pub days: Vec<u8>,
+    pub parts: Vec<u8>,
+    pub input: Input,
+    pub output: Output,
+}
+
+/// The outcome of one part, printed as a line of JSON by `run --json`.
+/// Timings are in nanoseconds, and parsing is timed once for both parts.
+#[derive(Debug, Serialize)]
+pub struct Record {
+    pub day: u8,
+    pub part: u8,
+    pub answer: Option<Answer>,
+    pub error: Option<String>,
+    pub parse_ns: Option<u64>,
+    pub part_ns: Option<u64>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    Help,
+    Run(Selection),
+    Verify {
+        selection: Selection,
+        answers: PathBuf,
+    },
+    Bench {
+        selection: Selection,
+        iterations: usize,
+        save: Option<PathBuf>,
+        baseline: Option<PathBuf>,
+    },
+    Rope(RopeOptions),
+    Trace(TraceOptions),
+    Draw(Picture),
+    Monkeys(MonkeyOptions),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct MonkeyOptions {
+    pub input: Input,
+    pub strictness: Strictness,
+    pub rules: Rules,
+    /// How many of the busiest monkeys make up the monkey business.
+    pub top: usize,
+    /// The rounds to report on, counting from 1.
+    pub after: Vec<usize>,
+    /// Report the items each monkey holds rather than its inspections.
+    pub holdings: bool,
+    pub history: Option<usize>,
+}
+
+/// What the program written by `draw` should show.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Picture {
+    Text(String),
+    Image(Input),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct TraceOptions {
+    pub input: Input,
+    pub breakpoints: Vec<Breakpoint>,
+    pub step: bool,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct RopeOptions {
+    pub input: Input,
+    pub knots: usize,
+    pub slack: u32,
+    pub steps: bool,
+    pub frames: Option<PathBuf>,
+    pub delay: Duration,
+}
+
+/// ```
+/// # use advent_of_code_2022::cli::{
+/// #     parse_args, Command, Input, Output, Selection,
+/// # };
+/// let args = ["run", "9", "2"].map(String::from);
+/// assert_eq!(
+///     parse_args(args).unwrap(),
+///     Command::Run(Selection {
+///         days: vec![9],
+///         parts: vec![2],
+///         input: Input::Default,
+///         output: Output::Text,
+///     })
+/// );
+/// ```
+pub fn parse_args(
+    args: impl IntoIterator<Item = String>,
+) -> Result<Command, CliError> {
+    let mut days = None;
+    let mut parts = None;
+    let mut input = Input::Default;
+    let mut output = Output::Text;
+    let mut answers = None;
+    let mut iterations = bench::DEFAULT_ITERATIONS;
+    let (mut save, mut baseline) = (None, None);
+    let mut args = args.into_iter().peekable();
+    let command = match args.peek().map(String::as_str) {
+        Some("rope") => return parse_rope_args(args.skip(1)),
+        Some("trace") => return parse_trace_args(args.skip(1)),
+        Some("draw") => return parse_draw_args(args.skip(1)),
+        Some("monkeys") => return parse_monkey_args(args.skip(1)),
+        Some(x @ ("run" | "verify" | "bench")) => {
+            let x = x.to_string();
+            args.next();
+            x
+        }
+        _ => "run".to_string(),
+    };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" | "help" => return Ok(Command::Help),
+            "-i" | "--input" if command == "run" => {
+                input = parse_input(args.next())?;
+            }
+            "--json" if command == "run" => output = Output::Json,
+            "-a" | "--answers" if command == "verify" => {
+                let file = args.next().ok_or(CliError::MissingFile(arg))?;
+                answers = Some(file.into());
+            }
+            "-n" | "--iterations" if command == "bench" => {
+                iterations = parse_number(&arg, args.next())?;
+            }
+            "--save" if command == "bench" => {
+                let file = args.next().ok_or(CliError::MissingFile(arg))?;
+                save = Some(file.into());
+            }
+            "--baseline" if command == "bench" => {
+                let file = args.next().ok_or(CliError::MissingFile(arg))?;
+                baseline = Some(file.into());
+            }
+            x if x.starts_with('-') => {
+                return Err(CliError::UnknownArgument(x.to_string()))
+            }
+            x if days.is_none() => days = Some(parse_days(x)?),
+            x if parts.is_none() => parts = Some(vec![parse_part(x)?]),
+            x => return Err(CliError::UnknownArgument(x.to_string())),
+        }
+    }
+    let days = match days {
+        Some(days) => days,
+        None => parse_days("all")?,
+    };
+    if days.len() != 1 && input != Input::Default {
+        return Err(CliError::InputForManyDays);
+    }
+    let selection = Selection {
+        days,
+        parts: parts.unwrap_or_else(|| vec![1, 2]),
+        input,
+        output,
+    };
+    Ok(match command.as_str() {
+        "verify" => Command::Verify {
+            selection,
+            answers: answers.unwrap_or_else(|| verify::DEFAULT_ANSWERS.into()),
+        },
+        "bench" => Command::Bench {
+            selection,
+            iterations,
+            save,
+            baseline,
+        },
+        _ => Command::Run(selection),
+    })
+}
+
+fn parse_trace_args(
+    mut args: impl Iterator<Item = String>,
+) -> Result<Command, CliError> {
+    let mut trace = TraceOptions {
+        input: Input::Default,
+        breakpoints: Vec::new(),
+        step: false,
+    };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" | "help" => return Ok(Command::Help),
+            "-i" | "--input" => trace.input = parse_input(args.next())?,
+            "-b" | "--break" => {
+                let when = args.next().unwrap_or_default();
+                let breakpoint = when
+                    .parse()
+                    .map_err(|_| CliError::InvalidBreakpoint(when))?;
+                trace.breakpoints.push(breakpoint);
+            }
+            "--step" => trace.step = true,
+            x => return Err(CliError::UnknownArgument(x.to_string())),
+        }
+    }
+    Ok(Command::Trace(trace))
+}
+
+fn parse_draw_args(
+    mut args: impl Iterator<Item = String>,
+) -> Result<Command, CliError> {
+    let mut picture = None;
+    while let Some(arg) = args.next() {
+        picture = Some(match arg.as_str() {
+            "-h" | "--help" | "help" => return Ok(Command::Help),
+            "--image" => match args.next() {
+                Some(x) if x == "-" => Picture::Image(Input::Stdin),
+                Some(x) => Picture::Image(Input::File(x.into())),
+                None => return Err(CliError::MissingFile(arg)),
+            },
+            x if picture.is_none() && !x.starts_with('-') => Picture::Text(arg),
+            x => return Err(CliError::UnknownArgument(x.to_string())),
+        });
+    }
+    picture.map(Command::Draw).ok_or(CliError::MissingImage)
+}
+
+fn parse_monkey_args(
+    mut args: impl Iterator<Item = String>,
+) -> Result<Command, CliError> {
+    let mut monkeys = MonkeyOptions {
+        input: Input::Default,
+        strictness: Strictness::Lenient,
+        rules: Rules::PART2,
+        top: 2,
+        after: Vec::new(),
+        holdings: false,
+        history: None,
+    };
+    let rules = &mut monkeys.rules;
+    let mut backend = None;
+    // The last option given that only matters when playing every monkey.
+    let mut not_for_history = None;
+    while let Some(arg) = args.next() {
+        if matches!(
+            arg.as_str(),
+            "--worry" | "--top" | "--after" | "--holdings"
+        ) {
+            not_for_history = Some(arg.clone());
+        }
+        match arg.as_str() {
+            "-h" | "--help" | "help" => return Ok(Command::Help),
+            "-i" | "--input" => monkeys.input = parse_input(args.next())?,
+            "--rounds" => rules.rounds = parse_number(&arg, args.next())?,
+            "--relief" => rules.relief = parse_number(&arg, args.next())?,
+            "--worry" => {
+                let worry = args.next().unwrap_or_default();
+                backend = Some(
+                    worry.parse().map_err(|_| CliError::InvalidWorry(worry))?,
+                );
+            }
+            "--top" => monkeys.top = parse_number(&arg, args.next())?,
+            "--after" => {
+                let rounds = args.next().unwrap_or_default();
+                for round in rounds.split(',') {
+                    let round = parse_number(&arg, Some(round.to_string()))?;
+                    monkeys.after.push(round);
+                }
+            }
+            "--holdings" => monkeys.holdings = true,
+            "--strict" => monkeys.strictness = Strictness::Strict,
+            "--history" => {
+                let item = args.next().and_then(|x| x.parse().ok());
+                monkeys.history = Some(item.ok_or(CliError::InvalidItem)?);
+            }
+            x => return Err(CliError::UnknownArgument(x.to_string())),
+        }
+    }
+    rules.backend = match backend {
+        Some(backend) => backend,
+        None if rules.relief != 1 => Backend::Big,
+        None => Backend::Modulo,
+    };
+    if monkeys.history.is_some() {
+        if rules.relief != 1 {
+            not_for_history = Some("--relief".to_string());
+        }
+        if let Some(arg) = not_for_history {
+            return Err(CliError::NotWithHistory(arg));
+        }
+    }
+    Ok(Command::Monkeys(monkeys))
+}
+
+fn parse_rope_args(
+    mut args: impl Iterator<Item = String>,
+) -> Result<Command, CliError> {
+    let mut rope = RopeOptions {
+        input: Input::Default,
+        knots: 10,
+        slack: day9::DEFAULT_SLACK,
+        steps: false,
+        frames: None,
+        delay: Duration::from_millis(100),
+    };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" | "help" => return Ok(Command::Help),
+            "-i" | "--input" => rope.input = parse_input(args.next())?,
+            "--knots" => rope.knots = parse_number(&arg, args.next())?,
+            "--slack" => rope.slack = parse_number(&arg, args.next())?,
+            "--steps" => rope.steps = true,
+            "--frames" => {
+                let dir = args.next().ok_or(CliError::MissingFile(arg))?;
+                rope.frames = Some(dir.into());
+            }
+            "--delay" => {
+                let ms = parse_number(&arg, args.next())?;
+                rope.delay = Duration::from_millis(ms);
+            }
+            x => return Err(CliError::UnknownArgument(x.to_string())),
+        }
+    }
+    Ok(Command::Rope(rope))
+}
+
+fn parse_input(arg: Option<String>) -> Result<Input, CliError> {
+    match arg.ok_or(CliError::MissingInput)? {
+        x if x == "-" => Ok(Input::Stdin),
+        x => Ok(Input::File(x.into())),
+    }
+}
+
+/// ```
+/// # use advent_of_code_2022::cli::parse_days;
+/// assert_eq!(parse_days("3..7").unwrap(), vec![3, 4, 5, 6, 7]);
+/// assert_eq!(parse_days("all").unwrap().len(), 11);
+/// assert!(parse_days("30").is_err());
+/// ```
+pub fn parse_days(arg: &str) -> Result<Vec<u8>, CliError> {
+    let invalid = || CliError::InvalidDays(arg.to_string());
+    if arg == "all" {
+        return Ok(DAYS.iter().map(|day| day.number).collect());
+    }
+    let days: Vec<u8> = match arg.split_once("..") {
+        Some((start, end)) => {
+            let start = start.parse().map_err(|_| invalid())?;
+            let end =
+                end.trim_start_matches('=').parse().map_err(|_| invalid())?;
+            (start..=end).collect()
+        }
+        None => vec![arg.parse().map_err(|_| invalid())?],
+    };
+    if days.is_empty() {
+        return Err(invalid());
+    }
+    for &number in &days {
+        day(number).ok_or(CliError::UnknownDay(number))?;
+    }
+    Ok(days)
+}
+
+fn parse_number<T: std::str::FromStr + Default + PartialOrd>(
+    flag: &str,
+    arg: Option<String>,
+) -> Result<T, CliError> {
+    match arg.map(|x| x.parse()) {
+        Some(Ok(n)) if n > T::default() => Ok(n),
+        _ => Err(CliError::InvalidNumber(flag.to_string())),
+    }
+}
+
+fn parse_part(arg: &str) -> Result<u8, CliError> {
+    match arg {
+        "1" => Ok(1),
+        "2" => Ok(2),
+        x => Err(CliError::InvalidPart(x.to_string())),
+    }
+}
+
+pub fn read_input(day: &Day, input: &Input) -> io::Result<String> {
+    match input {
+        Input::Default => fs::read_to_string(day.default_input()),
+        Input::File(path) => fs::read_to_string(path),
+        Input::Stdin => {
+            let mut text = String::new();
+            io::stdin().read_to_string(&mut text)?;
+            Ok(text)
+        }
+    }
+}
+
+/// Runs every selected day and part, printing answers to stdout and errors to
+/// stderr. Returns whether everything succeeded.
+pub fn run(selection: &Selection) -> bool {
+    if selection.output == Output::Json {
+        return run_json(selection);
+    }
+    let mut success = true;
+    for &number in &selection.days {
+        let day = day(number).expect("days are checked when parsed");
+        let input = match read_input(day, &selection.input) {
+            Ok(input) => input,
+            Err(err) => {
+                eprintln!("Day {number} {err}");
+                success = false;
+                continue;
+            }
+        };
+        for &part in &selection.parts {
+            if let Err(err) = print_result(number, part, day.run(&input, part))
+            {
+                eprintln!("Day {number} Puzzle {part} {err}");
+                success = false;
+            }
+        }
+    }
+    success
+}
+
+/// Like [`run`], but prints a [`Record`] for every day and part, errors
+/// included, to stdout.
+fn run_json(selection: &Selection) -> bool {
+    let mut success = true;
+    for &number in &selection.days {
+        let day = day(number).expect("days are checked when parsed");
+        let records = match read_input(day, &selection.input) {
+            Ok(input) => run_day(day, &input, &selection.parts),
+            Err(err) => failed_records(number, &selection.parts, &err, None),
+        };
+        for record in records {
+            success &= record.error.is_none();
+            println!(
+                "{}",
+                serde_json::to_string(&record).expect("records are valid JSON")
+            );
+        }
+    }
+    success
+}
+
+/// Parses `input` once and runs each part on it, timing both.
+///
+/// ```
+/// # use advent_of_code_2022::{cli::run_day, day, Answer};
+/// let records = run_day(day(1).unwrap(), "1\n2\n\n4", &[1, 2]);
+/// assert_eq!(records[0].answer, Some(Answer::Int(4)));
+/// assert_eq!(
+///     records[1].error.as_deref(),
+///     Some("day 1: not enough elves")
+/// );
+/// ```
+pub fn run_day(day: &Day, input: &str, parts: &[u8]) -> Vec<Record> {
+    let start = Instant::now();
+    let parsed = (day.parse)(input);
+    let parse_ns = Some(bench::nanos(start.elapsed()));
+    let solution = match parsed {
+        Ok(solution) => solution,
+        Err(err) => return failed_records(day.number, parts, &err, parse_ns),
+    };
+    parts
+        .iter()
+        .map(|&part| {
+            let start = Instant::now();
+            let result = day.run_parsed(&*solution, part);
+            let part_ns = Some(bench::nanos(start.elapsed()));
+            let (answer, error) = match result {
+                Ok(answer) => (Some(answer), None),
+                Err(err) => (None, Some(err.to_string())),
+            };
+            Record {
+                day: day.number,
+                part,
+                answer,
+                error,
+                parse_ns,
+                part_ns,
+            }
+        })
+        .collect()
+}
+
+fn failed_records(
+    day: u8,
+    parts: &[u8],
+    err: &impl ToString,
+    parse_ns: Option<u64>,
+) -> Vec<Record> {
+    parts
+        .iter()
+        .map(|&part| Record {
+            day,
+            part,
+            answer: None,
+            error: Some(err.to_string()),
+            parse_ns,
+            part_ns: None,
+        })
+        .collect()
+}
+
+/// Checks every selected day and part against the expected answers, printing
+/// a report. Returns whether nothing failed.
+pub fn verify(selection: &Selection, answers: &Answers) -> bool {
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+    for &number in &selection.days {
+        let day = day(number).expect("days are checked when parsed");
+        let checks = match read_input(day, &selection.input) {
+            Ok(input) => {
+                verify::verify_day(day, &input, &selection.parts, answers)
+            }
+            Err(err) => {
+                eprintln!("Day {number} {err}");
+                failed += selection.parts.len();
+                continue;
+            }
+        };
+        for check in checks {
+            println!("{check}");
+            if check.failed() {
+                failed += 1;
+            } else if matches!(check.outcome, verify::Outcome::Missing { .. }) {
+                missing += 1;
+            } else {
+                passed += 1;
+            }
+        }
+    }
+    println!("{passed} passed, {failed} failed, {missing} missing");
+    failed == 0
+}
+
+/// Times every selected day and part, printing each timing along with its
+/// change from `baseline`. Returns the timings and whether every day ran.
+pub fn bench(
+    selection: &Selection,
+    iterations: usize,
+    baseline: Option<&Report>,
+) -> (Report, bool) {
+    let mut report = Report::default();
+    let mut success = true;
+    for &number in &selection.days {
+        let day = day(number).expect("days are checked when parsed");
+        let timings = read_input(day, &selection.input)
+            .map_err(AocError::from)
+            .and_then(|input| {
+                bench::bench_day(day, &input, &selection.parts, iterations)
+            });
+        let timings = match timings {
+            Ok(timings) => timings,
+            Err(err) => {
+                eprintln!("Day {number} {err}");
+                success = false;
+                continue;
+            }
+        };
+        for timing in timings {
+            match baseline.and_then(|x| x.get(number, timing.stage)) {
+                Some(old) => {
+                    println!("{timing}  {:+.1}%", timing.change(old));
+                }
+                None => println!("{timing}"),
+            }
+            report.0.push(timing);
+        }
+    }
+    (report, success)
+}
+
+/// Animates the day 9 rope, then shows the cells its tail visited. Frames go
+/// to the terminal, or to files in `options.frames`. Returns whether it
+/// succeeded.
+pub fn rope(options: &RopeOptions) -> bool {
+    let day = day(9).expect("day 9 is solved");
+    let (rope, bounds, moves) = match read_input(day, &options.input)
+        .map_err(AocError::from)
+        .and_then(|input| day9::parse_moves(&input))
+        .and_then(|moves| Ok((day9::bounds(&moves)?, moves)))
+        .map(|(bounds, moves)| {
+            let rope = day9::Rope::with_slack(options.knots, options.slack)
+                .bounded(bounds);
+            (rope, bounds, moves)
+        }) {
+        Ok(x) => x,
+        Err(err) => {
+            eprintln!("Day 9 {err}");
+            return false;
+        }
+    };
+    if let Some(dir) = &options.frames {
+        if let Err(err) = fs::create_dir_all(dir) {
+            eprintln!("{}: {err}", dir.display());
+            return false;
+        }
+    }
+    let mut count = 0;
+    let mut show = |frame: &str| {
+        count += 1;
+        if let Some(dir) = &options.frames {
+            return fs::write(dir.join(format!("{count:05}.txt")), frame);
+        }
+        print!("\x1b[2J\x1b[H{frame}");
+        thread::sleep(options.delay);
+        Ok(())
+    };
+    let result = day9::animate(&moves, rope, bounds, options.steps, &mut show)
+        .and_then(|rope| {
+            let visited = day9::draw_visited(&rope, bounds);
+            show(&format!(
+                "== Visited by the tail: {} ==\n\n{visited}\n",
+                rope.visited().len()
+            ))
+        });
+    if let Err(err) = result {
+        eprintln!("{err}");
+        return false;
+    }
+    true
+}
+
+/// Assembles and runs a day 10 program, printing its state during the
+/// selected cycles or stepping through it on command. Returns whether the
+/// program could be read.
+pub fn trace(options: &TraceOptions) -> bool {
+    let day = day(10).expect("day 10 is solved");
+    let input = match read_input(day, &options.input) {
+        Ok(input) => input,
+        Err(err) => {
+            eprintln!("Day 10 {err}");
+            return false;
+        }
+    };
+    let program = match day10::assemble(&input) {
+        Ok(program) => program,
+        Err(errors) => {
+            eprintln!("{errors}");
+            return false;
+        }
+    };
+    let mut computer = Computer::new(program);
+    let breakpoints = &options.breakpoints;
+    if !options.step {
+        while let Some(state) = computer.state() {
+            if breakpoints.is_empty()
+                || breakpoints.iter().any(|x| x.is_hit(&state))
+            {
+                println!("{state}");
+            }
+            computer.tick();
+        }
+        return !overflowed(&computer);
+    }
+    let mut line = String::new();
+    while let Some(state) = computer.state() {
+        println!("{state}");
+        line.clear();
+        match io::stdin().read_line(&mut line) {
+            Ok(0) | Err(_) => return true,
+            Ok(_) => {}
+        }
+        match line.trim() {
+            "" | "s" => {
+                computer.tick();
+            }
+            "c" => {
+                computer.tick();
+                if breakpoints.is_empty() {
+                    while computer.tick() {}
+                } else {
+                    computer.run_until(breakpoints);
+                }
+            }
+            "q" => return true,
+            x => eprintln!("unknown command {x}, use enter, c or q"),
+        }
+    }
+    if overflowed(&computer) {
+        return false;
+    }
+    println!("halted after {} cycles", computer.cycle);
+    true
+}
+
+/// Prints an error and returns `true` if `computer` halted because a
+/// register overflowed.
+fn overflowed(computer: &Computer) -> bool {
+    let Some(err) = computer.overflow() else {
+        return false;
+    };
+    eprintln!("Day 10 {err}");
+    true
+}
+
+/// Prints a day 10 program that draws `picture`. Returns whether it could be
+/// drawn.
+pub fn draw(picture: &Picture) -> bool {
+    let puzzle = |err: day10::DrawError| AocError::puzzle(10, err.to_string());
+    let crt = match picture {
+        Picture::Text(text) => day10::Crt::from_letters(text).map_err(puzzle),
+        Picture::Image(input) => {
+            let day = day(10).expect("day 10 is solved");
+            match read_input(day, input) {
+                Ok(text) => day10::Crt::parse(&text),
+                Err(err) => {
+                    eprintln!("Day 10 {err}");
+                    return false;
+                }
+            }
+        }
+    };
+    match crt.and_then(|crt| day10::synthesize(&crt).map_err(puzzle)) {
+        Ok(program) => {
+            print!("{}", day10::disassemble(&program));
+            true
+        }
+        Err(err) => {
+            eprintln!("{err}");
+            false
+        }
+    }
+}
+
+/// Plays day 11 with the chosen rules and prints the monkey business, or
+/// the path of one item. Returns whether it could be played.
+pub fn monkeys(options: &MonkeyOptions) -> bool {
+    let day = day(11).expect("day 11 is solved");
+    let monkeys = read_input(day, &options.input)
+        .map_err(AocError::from)
+        .and_then(|input| {
+            day11::parse_monkeys_with(&input, options.strictness)
+        });
+    if let Some(item) = options.history {
+        let history = monkeys.and_then(|monkeys| {
+            day11::item_history(&monkeys, item, options.rules.rounds)
+        });
+        return match history {
+            Ok(history) => {
+                for (round, visits) in history.iter().enumerate() {
+                    println!(
+                        "Round {}: {}",
+                        round + 1,
+                        visits.iter().join(" -> ")
+                    );
+                }
+                true
+            }
+            Err(err) => {
+                eprintln!("Day 11 {err}");
+                false
+            }
+        };
+    }
+    let inspections = monkeys.and_then(|monkeys| {
+        day11::inspections(&monkeys, &options.rules, &options.after, |report| {
+            if options.holdings {
+                println!("{}", report.holdings());
+            } else {
+                println!("{report}");
+            }
+        })
+    });
+    let business =
+        inspections.and_then(|x| day11::monkey_business(&x, options.top));
+    match business {
+        Ok(business) => {
+            println!(
+                "Monkey business after {} rounds: {business}",
+                options.rules.rounds
+            );
+            true
+        }
+        Err(err) => {
+            eprintln!("Day 11 {err}");
+            false
+        }
+    }
+}
+
+fn print_result(
+    number: u8,
+    part: u8,
+    result: Result<Answer, AocError>,
+) -> Result<(), AocError> {
+    match result? {
+        Answer::Text(x) if x.contains('\n') => {
+            println!("Day {number} Puzzle {part}:\n{x}");
+        }
+        answer => println!("Day {number} Puzzle {part} {answer}"),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::cli::{
+        self, CliError, Command, Input, Output, Picture, Selection,
+    };
+    use crate::day10::{Breakpoint, Register};
+    use crate::day11::{Backend, Rules, Strictness};
+    use std::time::Duration;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn can_run_all_by_default() {
+        let Ok(Command::Run(selection)) = cli::parse_args(args("")) else {
+            panic!("expected a run command");
+        };
+        assert_eq!(selection.days, cli::parse_days("all").unwrap());
+        assert_eq!(selection.parts, vec![1, 2]);
+        assert_eq!(
+            cli::parse_args(args("run all")),
+            Ok(Command::Run(selection))
+        );
+    }
+
+    #[test]
+    fn can_parse_range_and_stdin() {
+        assert_eq!(
+            cli::parse_args(args("4..4 1 --input -")),
+            Ok(Command::Run(Selection {
+                days: vec![4],
+                parts: vec![1],
+                input: Input::Stdin,
+                output: Output::Text,
+            }))
+        );
+    }
+
+    #[test]
+    fn cannot_give_input_for_many_days() {
+        assert_eq!(
+            cli::parse_args(args("run 3..7 --input day3.txt")),
+            Err(CliError::InputForManyDays)
+        );
+        assert_eq!(
+            cli::parse_args(args("run 3 3")),
+            Err(CliError::InvalidPart("3".to_string()))
+        );
+        assert_eq!(
+            cli::parse_args(args("verify 3 --input day3.txt")),
+            Err(CliError::UnknownArgument("--input".to_string()))
+        );
+    }
+
+    #[test]
+    fn can_parse_verify() {
+        assert_eq!(
+            cli::parse_args(args("verify 9 --answers old.toml")),
+            Ok(Command::Verify {
+                selection: Selection {
+                    days: vec![9],
+                    parts: vec![1, 2],
+                    input: Input::Default,
+                    output: Output::Text,
+                },
+                answers: "old.toml".into(),
+            })
+        );
+    }
+
+    #[test]
+    fn can_print_json() {
+        let Ok(Command::Run(selection)) = cli::parse_args(args("6 1 --json"))
+        else {
+            panic!("expected a run command");
+        };
+        assert_eq!(selection.output, Output::Json);
+        let records = cli::run_day(cli::day(6).unwrap(), "abcde", &[1]);
+        let json = serde_json::to_value(&records[0]).unwrap();
+        assert_eq!(json["day"], 6);
+        assert_eq!(json["answer"], 5);
+        assert!(json["error"].is_null());
+        assert!(json["parse_ns"].is_u64() && json["part_ns"].is_u64());
+        let records = cli::run_day(cli::day(6).unwrap(), "aaaa", &[2]);
+        let json = serde_json::to_value(&records[0]).unwrap();
+        assert!(json["answer"].is_null());
+        assert_eq!(json["error"], "day 6: no start-of-message marker");
+    }
+
+    #[test]
+    fn can_parse_monkeys() {
+        assert_eq!(
+            cli::parse_args(args("monkeys --rounds 20 --relief 3 --worry big")),
+            Ok(Command::Monkeys(cli::MonkeyOptions {
+                input: Input::Default,
+                strictness: Strictness::Lenient,
+                rules: Rules {
+                    rounds: 20,
+                    relief: 3,
+                    backend: Backend::Big,
+                },
+                top: 2,
+                after: Vec::new(),
+                holdings: false,
+                history: None,
+            }))
+        );
+        let Ok(Command::Monkeys(options)) = cli::parse_args(args(
+            "monkeys --after 1,20,1000 --holdings --top 3 --strict",
+        )) else {
+            panic!("monkeys options should parse");
+        };
+        assert_eq!(options.after, vec![1, 20, 1000]);
+        assert_eq!((options.top, options.holdings), (3, true));
+        assert_eq!(options.strictness, Strictness::Strict);
+        let Ok(Command::Monkeys(options)) =
+            cli::parse_args(args("monkeys --relief 2"))
+        else {
+            panic!("monkeys options should parse");
+        };
+        assert_eq!(options.rules.backend, Backend::Big);
+        assert_eq!(
+            cli::parse_args(args("monkeys --after 1,,2")),
+            Err(CliError::InvalidNumber("--after".to_string()))
+        );
+        assert_eq!(
+            cli::parse_args(args("monkeys --worry huge")),
+            Err(CliError::InvalidWorry("huge".to_string()))
+        );
+        assert_eq!(
+            cli::parse_args(args("monkeys --history x")),
+            Err(CliError::InvalidItem)
+        );
+        assert!(cli::parse_args(args("monkeys --history 0 --relief 1")).is_ok());
+        assert_eq!(
+            cli::parse_args(args("monkeys --history 0 --rounds 2 --relief 3")),
+            Err(CliError::NotWithHistory("--relief".to_string()))
+        );
+        assert_eq!(
+            cli::parse_args(args("monkeys --top 3 --history 0")),
+            Err(CliError::NotWithHistory("--top".to_string()))
+        );
+    }
+
+    #[test]
+    fn can_parse_draw() {
+        assert_eq!(
+            cli::parse_args(args("draw ZGCJZJFL")),
+            Ok(Command::Draw(Picture::Text("ZGCJZJFL".to_string())))
+        );
+        assert_eq!(
+            cli::parse_args(args("draw --image -")),
+            Ok(Command::Draw(Picture::Image(Input::Stdin)))
+        );
+        assert_eq!(cli::parse_args(args("draw")), Err(CliError::MissingImage));
+        assert_eq!(
+            cli::parse_args(args("draw AB CD")),
+            Err(CliError::UnknownArgument("CD".to_string()))
+        );
+    }
+
+    #[test]
+    fn can_parse_trace() {
+        assert_eq!(
+            cli::parse_args(args("trace --break cycle=20 -b y=-1 --step")),
+            Ok(Command::Trace(cli::TraceOptions {
+                input: Input::Default,
+                breakpoints: vec![
+                    Breakpoint::Cycle(20),
+                    Breakpoint::Register(Register::Y, -1),
+                ],
+                step: true,
+            }))
+        );
+        assert_eq!(
+            cli::parse_args(args("trace --break z=1")),
+            Err(CliError::InvalidBreakpoint("z=1".to_string()))
+        );
+        assert_eq!(
+            cli::parse_args(args("trace --break cycle=-3")),
+            Err(CliError::InvalidBreakpoint("cycle=-3".to_string()))
+        );
+    }
+
+    #[test]
+    fn can_parse_rope() {
+        assert_eq!(
+            cli::parse_args(args("rope --knots 2 --steps --frames out")),
+            Ok(Command::Rope(cli::RopeOptions {
+                input: Input::Default,
+                knots: 2,
+                slack: 1,
+                steps: true,
+                frames: Some("out".into()),
+                delay: Duration::from_millis(100),
+            }))
+        );
+        assert_eq!(
+            cli::parse_args(args("rope 9")),
+            Err(CliError::UnknownArgument("9".to_string()))
+        );
+        assert_eq!(
+            cli::parse_args(args("rope --knots 0")),
+            Err(CliError::InvalidNumber("--knots".to_string()))
+        );
+    }
+
+    #[test]
+    fn can_parse_bench() {
+        assert_eq!(
+            cli::parse_args(args("bench 8 2 -n 50 --baseline base.json")),
+            Ok(Command::Bench {
+                selection: Selection {
+                    days: vec![8],
+                    parts: vec![2],
+                    input: Input::Default,
+                    output: Output::Text,
+                },
+                iterations: 50,
+                save: None,
+                baseline: Some("base.json".into()),
+            })
+        );
+        assert_eq!(
+            cli::parse_args(args("bench --iterations 0")),
+            Err(CliError::InvalidNumber("--iterations".to_string()))
+        );
+    }
+}
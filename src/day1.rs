@@ -1,6 +1,8 @@
-use std::error::Error;
+use crate::{input, Answer, AocError, Solution};
 use std::fs;
 
+const DAY: u8 = 1;
+
 #[derive(Default, Eq, Ord, PartialEq, PartialOrd, Debug, Clone)]
 pub struct Elf {
     pub cals: i32,
@@ -25,10 +27,11 @@ pub fn ints_to_elves(ints: Vec<Option<i32>>) -> Vec<Elf> {
 
     for int in ints {
         match int {
-            Some(num) => match elves.last_mut() {
-                Some(elf) => elf.add(num),
-                None => continue,
-            },
+            Some(num) => {
+                if let Some(elf) = elves.last_mut() {
+                    elf.add(num);
+                }
+            }
             None => elves.push(Elf::new()),
         }
     }
@@ -46,42 +49,94 @@ pub fn parse_lines(input: &str) -> Vec<Option<i32>> {
     output
 }
 
+/// ```
+/// # use advent_of_code_2022::day1::{parse_elves, Elf};
+/// assert_eq!(
+///     parse_elves("1\r\n2\r\n\r\n3\r\n").unwrap(),
+///     vec![Elf::from(3), Elf::from(3)]
+/// );
+pub fn parse_elves(input: &str) -> Result<Vec<Elf>, AocError> {
+    let mut elves = Vec::new();
+    for section in input::sections(input) {
+        let mut elf = Elf::new();
+        let cals = input::parse_lines(section, parse_calories)
+            .map_err(|err| err.offset_lines(input::line_of(input, section)))?;
+        for cals in cals {
+            elf.add(cals);
+        }
+        elves.push(elf);
+    }
+    if elves.is_empty() {
+        return Err(AocError::puzzle(DAY, "no elves in input"));
+    }
+    Ok(elves)
+}
+
+fn parse_calories(line: &str) -> Result<i32, AocError> {
+    let cals = line.trim();
+    cals.parse().map_err(|err| {
+        AocError::parse(DAY, line, cals, format!("invalid calories, {err}"))
+    })
+}
+
 pub fn max_elf(elves: &[Elf]) -> Elf {
     elves.iter().max().unwrap().clone()
 }
 
-pub fn top_3_elves(elves: Vec<Elf>) -> Result<Vec<Elf>, &'static str> {
+pub fn top_3_elves(elves: Vec<Elf>) -> Result<Vec<Elf>, AocError> {
     let mut start = elves;
     let mut end = Vec::new();
     start.sort();
     for _ in 0..3 {
         end.push(match start.pop() {
             Some(x) => x,
-            None => return Err("Not enough elves"),
+            None => return Err(AocError::puzzle(DAY, "not enough elves")),
         });
     }
     Ok(end)
 }
 
 pub fn sum_elves(elves: Vec<Elf>) -> i32 {
-    elves
-        .into_iter()
-        .reduce(|mut acc, e| {
-            acc.add(e.cals);
-            acc
-        })
-        .unwrap()
-        .cals
+    elves.into_iter().map(|elf| elf.cals).sum()
+}
+
+/// ```
+/// # use advent_of_code_2022::day1::solve_part1;
+/// assert_eq!(solve_part1("1\n2\n\n4").unwrap(), 4);
+pub fn solve_part1(input: &str) -> Result<i32, AocError> {
+    Ok(max_elf(&parse_elves(input)?).cals)
 }
 
-pub fn puzzle1(path: &str) -> Result<i32, Box<dyn Error>> {
-    Ok(max_elf(&ints_to_elves(parse_lines(&fs::read_to_string(path)?))).cals)
+pub fn solve_part2(input: &str) -> Result<i32, AocError> {
+    Ok(sum_elves(top_3_elves(parse_elves(input)?)?))
 }
 
-pub fn puzzle2(path: &str) -> Result<i32, Box<dyn Error>> {
-    Ok(sum_elves(top_3_elves(ints_to_elves(parse_lines(
-        &fs::read_to_string(path)?,
-    )))?))
+pub fn puzzle1(path: &str) -> Result<i32, AocError> {
+    solve_part1(&fs::read_to_string(path)?)
+}
+
+pub fn puzzle2(path: &str) -> Result<i32, AocError> {
+    solve_part2(&fs::read_to_string(path)?)
+}
+
+pub struct Day1 {
+    elves: Vec<Elf>,
+}
+
+impl Solution for Day1 {
+    fn parse(input: &str) -> Result<Self, AocError> {
+        Ok(Day1 {
+            elves: parse_elves(input)?,
+        })
+    }
+
+    fn part1(&self) -> Result<Answer, AocError> {
+        Ok(max_elf(&self.elves).cals.into())
+    }
+
+    fn part2(&self) -> Result<Answer, AocError> {
+        Ok(sum_elves(top_3_elves(self.elves.clone())?).into())
+    }
 }
 
 #[cfg(test)]
@@ -135,6 +190,16 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn cannot_parse_invalid_calories() {
+        let err = day1::parse_elves("1\n2\n\n3\n4x").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "day 1, line 5, column 1: invalid calories, invalid digit found \
+             in string (\"4x\")"
+        );
+    }
+
     #[test]
     fn can_find_max_elf() {
         assert_eq!(
@@ -152,8 +217,9 @@ pub mod tests {
                 Elf::from(0),
                 Elf::from(15),
                 Elf::from(3)
-            ]),
-            Ok(vec![Elf::from(15), Elf::from(12), Elf::from(9)])
+            ])
+            .unwrap(),
+            vec![Elf::from(15), Elf::from(12), Elf::from(9)]
         );
     }
 
@@ -1,123 +1,1142 @@
-use std::{error::Error, fs, num::ParseIntError};
+use crate::grid::Grid;
+use crate::{input, Answer, AocError, Solution};
+use std::{
+    collections::HashMap,
+    fmt, fs,
+    num::{NonZeroU64, ParseIntError},
+    str::{FromStr, SplitWhitespace},
+};
 use thiserror;
 use InstructionParseError as IPE;
 
-#[derive(Clone, PartialEq, Eq)]
+const DAY: u8 = 10;
+
+/// The registers of the [`Computer`]. The puzzle only uses `x`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Register {
+    X,
+    Y,
+}
+
+impl Register {
+    pub const ALL: [Register; 2] = [Register::X, Register::Y];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl TryFrom<&str> for Register {
+    type Error = InstructionParseError;
+    fn try_from(name: &str) -> Result<Self, Self::Error> {
+        match name {
+            "x" => Ok(Register::X),
+            "y" => Ok(Register::Y),
+            x => Err(IPE::InvalidRegister(x.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Register {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Register::X => "x",
+            Register::Y => "y",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Instruction {
-    Add(i32),
     Noop,
+    /// Adds to a register.
+    Add(Register, i32),
+    /// Multiplies a register.
+    Mul(Register, i32),
+    /// Moves by an offset in the program, so `jmp 1` does nothing.
+    Jmp(i32),
+    /// Moves by an offset if the register isn't zero.
+    Jnz(Register, i32),
+}
+
+/// The spelling of an instruction, how many cycles it takes, and how to read
+/// its arguments.
+pub struct Opcode {
+    pub mnemonic: &'static str,
+    pub cycles: u32,
+    parse: fn(&mut SplitWhitespace) -> Result<Instruction, IPE>,
+}
+
+/// Every instruction the [`Computer`] understands, in the order of
+/// [`Instruction::opcode`].
+///
+/// ```
+/// # use advent_of_code_2022::day10::{Instruction, OPCODES};
+/// for opcode in &OPCODES {
+///     let args = match opcode.mnemonic {
+///         "noop" => "",
+///         "jnz" => " x 1",
+///         _ => " 1",
+///     };
+///     let text = format!("{}{args}", opcode.mnemonic);
+///     let instruction = Instruction::try_from(text.as_str()).unwrap();
+///     assert_eq!(instruction.mnemonic(), opcode.mnemonic);
+/// }
+/// ```
+pub const OPCODES: [Opcode; 7] = [
+    Opcode {
+        mnemonic: "noop",
+        cycles: 1,
+        parse: |_| Ok(Instruction::Noop),
+    },
+    Opcode {
+        mnemonic: "addx",
+        cycles: 2,
+        parse: |args| Ok(Instruction::Add(Register::X, number(args)?)),
+    },
+    Opcode {
+        mnemonic: "addy",
+        cycles: 2,
+        parse: |args| Ok(Instruction::Add(Register::Y, number(args)?)),
+    },
+    Opcode {
+        mnemonic: "mulx",
+        cycles: 3,
+        parse: |args| Ok(Instruction::Mul(Register::X, number(args)?)),
+    },
+    Opcode {
+        mnemonic: "muly",
+        cycles: 3,
+        parse: |args| Ok(Instruction::Mul(Register::Y, number(args)?)),
+    },
+    Opcode {
+        mnemonic: "jmp",
+        cycles: 1,
+        parse: |args| Ok(Instruction::Jmp(number(args)?)),
+    },
+    Opcode {
+        mnemonic: "jnz",
+        cycles: 2,
+        parse: |args| {
+            let register = args.next().ok_or(IPE::NotEnoughArguments)?;
+            Ok(Instruction::Jnz(register.try_into()?, number(args)?))
+        },
+    },
+];
+
+fn number(args: &mut SplitWhitespace) -> Result<i32, IPE> {
+    let arg = args.next().ok_or(IPE::NotEnoughArguments)?;
+    arg.parse().map_err(|source| IPE::ParseIntError {
+        arg: arg.to_string(),
+        source,
+    })
+}
+
+impl Instruction {
+    /// The instruction's entry in [`OPCODES`].
+    pub fn opcode(&self) -> &'static Opcode {
+        &OPCODES[match self {
+            Instruction::Noop => 0,
+            Instruction::Add(Register::X, _) => 1,
+            Instruction::Add(Register::Y, _) => 2,
+            Instruction::Mul(Register::X, _) => 3,
+            Instruction::Mul(Register::Y, _) => 4,
+            Instruction::Jmp(_) => 5,
+            Instruction::Jnz(..) => 6,
+        }]
+    }
+
+    pub fn mnemonic(&self) -> &'static str {
+        self.opcode().mnemonic
+    }
+
+    /// How many cycles the instruction takes before its effect is seen.
+    pub fn cycles(&self) -> u32 {
+        self.opcode().cycles
+    }
+
+    /// How far a jump moves through the program.
+    pub fn offset(&self) -> Option<i32> {
+        match *self {
+            Instruction::Jmp(n) | Instruction::Jnz(_, n) => Some(n),
+            _ => None,
+        }
+    }
+
+    fn offset_mut(&mut self) -> Option<&mut i32> {
+        match self {
+            Instruction::Jmp(n) | Instruction::Jnz(_, n) => Some(n),
+            _ => None,
+        }
+    }
+}
+
+/// Writes the instruction as it is read, so that it parses back unchanged.
+///
+/// ```
+/// # use advent_of_code_2022::day10::{Instruction, Register};
+/// let jump = Instruction::Jnz(Register::Y, -2);
+/// assert_eq!(jump.to_string(), "jnz y -2");
+/// assert_eq!(Instruction::try_from("jnz y -2").unwrap(), jump);
+/// assert_eq!(Instruction::Mul(Register::X, 3).to_string(), "mulx 3");
+/// ```
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mnemonic = self.mnemonic();
+        match self {
+            Instruction::Noop => write!(f, "{mnemonic}"),
+            Instruction::Add(_, n)
+            | Instruction::Mul(_, n)
+            | Instruction::Jmp(n) => write!(f, "{mnemonic} {n}"),
+            Instruction::Jnz(r, n) => write!(f, "{mnemonic} {r} {n}"),
+        }
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
 pub enum InstructionParseError {
     #[error("not enough arguments in line to parse instruction")]
     NotEnoughArguments,
-    #[error("instruction {0} not valid, use addx [int] or noop")]
+    #[error("instruction {0} not valid, use one of {}", mnemonics())]
     InvalidInstruction(String),
-    #[error("{0}")]
-    ParseIntError(#[from] ParseIntError),
+    #[error("register {0} not valid, use x or y")]
+    InvalidRegister(String),
+    #[error("{source}")]
+    ParseIntError { arg: String, source: ParseIntError },
+    /// An argument after all of the instruction's own, where `index` counts
+    /// the words of the line from the mnemonic at 0.
+    #[error("unexpected argument {arg}")]
+    ExtraArgument { arg: String, index: usize },
+    #[error("label {0} not valid, use letters, digits and _")]
+    InvalidLabel(String),
+    #[error("label {0} is defined more than once")]
+    DuplicateLabel(String),
+    #[error("label {0} is not defined")]
+    UnknownLabel(String),
 }
 
+fn mnemonics() -> String {
+    OPCODES.map(|x| x.mnemonic).join(", ")
+}
+
+impl InstructionParseError {
+    /// Places the error at the argument of `line` that caused it.
+    pub fn locate(self, line: &str) -> AocError {
+        self.locate_in(line, line)
+    }
+
+    /// Places the error within `part`, a slice of `line` holding either the
+    /// instruction or, for an invalid or repeated label, the label.
+    fn locate_in(self, line: &str, part: &str) -> AocError {
+        let mut args = part.split_whitespace();
+        let arg = match &self {
+            IPE::NotEnoughArguments => "",
+            IPE::InvalidInstruction(_) => args.next().unwrap_or_default(),
+            IPE::InvalidRegister(x)
+            | IPE::ParseIntError { arg: x, .. }
+            | IPE::UnknownLabel(x) => {
+                args.skip(1).find(|arg| arg == x).unwrap_or_default()
+            }
+            IPE::ExtraArgument { index, .. } => {
+                args.nth(*index).unwrap_or_default()
+            }
+            IPE::InvalidLabel(_) | IPE::DuplicateLabel(_) => part,
+        };
+        AocError::parse(DAY, line, arg, self.to_string())
+    }
+}
+
+/// Reads an instruction by looking its mnemonic up in [`OPCODES`].
+///
+/// ```
+/// # use advent_of_code_2022::day10::{Instruction, Register};
+/// let jump = Instruction::try_from("jnz y -2").unwrap();
+/// assert!(jump == Instruction::Jnz(Register::Y, -2));
+/// assert!(Instruction::try_from("mulx 3").unwrap().cycles() == 3);
+/// assert!(Instruction::try_from("noop 5 6").is_err());
+/// ```
 impl TryFrom<&str> for Instruction {
     type Error = InstructionParseError;
     fn try_from(line: &str) -> Result<Self, Self::Error> {
         let mut args = line.split_whitespace();
-        match args.next().ok_or(IPE::NotEnoughArguments)? {
-            "noop" => Ok(Instruction::Noop),
-            "addx" => Ok(Instruction::Add(
-                args.next().ok_or(IPE::NotEnoughArguments)?.parse()?,
-            )),
-            x => Err(IPE::InvalidInstruction(x.to_string())),
+        let mnemonic = args.next().ok_or(IPE::NotEnoughArguments)?;
+        let opcode = OPCODES
+            .iter()
+            .find(|x| x.mnemonic == mnemonic)
+            .ok_or_else(|| IPE::InvalidInstruction(mnemonic.to_string()))?;
+        let instruction = (opcode.parse)(&mut args)?;
+        match args.next() {
+            Some(extra) => Err(IPE::ExtraArgument {
+                arg: extra.to_string(),
+                index: line.split_whitespace().count() - args.count() - 1,
+            }),
+            None => Ok(instruction),
         }
     }
 }
 
+/// Runs a program one clock cycle at a time. An instruction's effect is seen
+/// once all of its cycles have finished, and the program halts when it moves
+/// outside the program or a register overflows.
 #[derive(Clone)]
 pub struct Computer {
-    pub x: i32,
-    pub cycle: i32,
-    instruction: Instruction,
-    wait: bool,
+    pub registers: [i32; Register::ALL.len()],
+    /// The number of cycles that have finished.
+    pub cycle: u64,
+    /// The index of the instruction being run.
+    pub pc: usize,
+    /// The cycles spent so far on the instruction being run.
+    busy: u32,
+    /// The register that overflowed during the last cycle, if one did.
+    overflowed: Option<Register>,
+    program: Vec<Instruction>,
 }
 
-impl Default for Computer {
-    fn default() -> Self {
+impl Computer {
+    /// A computer at the start of `program`, with `x` set to 1 and every
+    /// other register 0.
+    pub fn new(program: Vec<Instruction>) -> Computer {
+        let mut registers = [0; Register::ALL.len()];
+        registers[Register::X.index()] = 1;
         Computer {
-            x: 1,
+            registers,
             cycle: 0,
-            instruction: Instruction::Noop,
-            wait: false,
+            pc: 0,
+            busy: 0,
+            overflowed: None,
+            program,
         }
     }
-}
 
-impl Computer {
-    pub fn set_instruction(&mut self, instruction: Instruction) {
-        self.wait = false;
-        if let Instruction::Add(_) = instruction {
-            self.wait = true;
+    pub fn register(&self, register: Register) -> i32 {
+        self.registers[register.index()]
+    }
+
+    pub fn x(&self) -> i32 {
+        self.register(Register::X)
+    }
+
+    /// The instruction being run, or `None` if the program has halted.
+    pub fn instruction(&self) -> Option<Instruction> {
+        if self.overflowed.is_some() {
+            return None;
         }
-        self.instruction = instruction;
+        self.program.get(self.pc).copied()
     }
 
-    pub fn cycle_clock(&mut self) -> bool {
+    /// Why the program halted, if it was because a register overflowed.
+    ///
+    /// ```
+    /// # use advent_of_code_2022::day10::{parse_program, Computer};
+    /// let program = parse_program("mulx 2147483647\nmulx 2").unwrap();
+    /// let mut computer = Computer::new(program);
+    /// while computer.tick() {}
+    /// assert_eq!(computer.x(), i32::MAX);
+    /// assert_eq!(
+    ///     computer.overflow().unwrap().to_string(),
+    ///     "day 10: register x overflowed during cycle 6"
+    /// );
+    /// ```
+    pub fn overflow(&self) -> Option<AocError> {
+        let register = self.overflowed?;
+        Some(AocError::puzzle(
+            DAY,
+            format!(
+                "register {register} overflowed during cycle {}",
+                self.cycle
+            ),
+        ))
+    }
+
+    /// The state during the next cycle, or `None` if the program has halted.
+    pub fn state(&self) -> Option<State> {
+        Some(State {
+            cycle: self.cycle + 1,
+            registers: self.registers,
+            pc: self.pc,
+            instruction: self.instruction()?,
+        })
+    }
+
+    /// The state during each cycle until the program halts.
+    pub fn trace(self) -> Trace {
+        Trace { computer: self }
+    }
+
+    /// Runs until the state during the next cycle hits one of `breakpoints`,
+    /// and returns it without running that cycle. Returns `None` if the
+    /// program halts first. A breakpoint that is already hit stops it straight
+    /// away, so [`Computer::tick`] past it before continuing.
+    ///
+    /// ```
+    /// # use advent_of_code_2022::day10::{parse_program, Breakpoint, Computer};
+    /// let program = parse_program("noop\naddx 3\naddx -5").unwrap();
+    /// let mut computer = Computer::new(program);
+    /// let state = computer.run_until(&["x=4".parse().unwrap()]).unwrap();
+    /// assert_eq!(state.cycle, 4);
+    /// let stop = Breakpoint::Cycle(5);
+    /// assert_eq!(computer.run_until(&[stop]).unwrap().x(), 4);
+    /// computer.tick();
+    /// assert!(computer.run_until(&[stop]).is_none());
+    /// ```
+    pub fn run_until(&mut self, breakpoints: &[Breakpoint]) -> Option<State> {
+        loop {
+            let state = self.state()?;
+            if breakpoints.iter().any(|x| x.is_hit(&state)) {
+                return Some(state);
+            }
+            self.tick();
+        }
+    }
+
+    /// Runs one cycle, returning `false` without doing anything if the
+    /// program has halted, or if it halts because a register overflows.
+    ///
+    /// ```
+    /// # use advent_of_code_2022::day10::{parse_program, Computer};
+    /// let program = parse_program("addx 3\nmulx -2").unwrap();
+    /// let mut computer = Computer::new(program);
+    /// while computer.tick() {}
+    /// assert_eq!((computer.x(), computer.cycle), (-8, 5));
+    ///
+    /// // Adds 2 to x three times.
+    /// let program = parse_program("addy 3\naddx 2\naddy -1\njnz y -2");
+    /// let mut computer = Computer::new(program.unwrap());
+    /// while computer.tick() {}
+    /// assert_eq!(computer.x(), 7);
+    /// ```
+    pub fn tick(&mut self) -> bool {
+        let Some(instruction) = self.instruction() else {
+            return false;
+        };
         self.cycle += 1;
-        match self.instruction {
-            Instruction::Add(x) => {
-                if self.wait {
-                    self.wait = false;
-                    false
-                } else {
-                    self.x += x;
-                    true
-                }
+        self.busy += 1;
+        if self.busy < instruction.cycles() {
+            return true;
+        }
+        self.busy = 0;
+        let mut offset = 1;
+        match instruction {
+            Instruction::Add(r, n) => {
+                self.store(r, self.register(r).checked_add(n));
             }
-            Instruction::Noop => true,
+            Instruction::Mul(r, n) => {
+                self.store(r, self.register(r).checked_mul(n));
+            }
+            Instruction::Jmp(n) => offset = n,
+            Instruction::Jnz(r, n) if self.register(r) != 0 => offset = n,
+            Instruction::Noop | Instruction::Jnz(..) => {}
+        }
+        if self.overflowed.is_some() {
+            return false;
+        }
+        // Jumping before the start halts the program just like jumping past
+        // the end.
+        self.pc = self
+            .pc
+            .checked_add_signed(offset as isize)
+            .unwrap_or(usize::MAX);
+        true
+    }
+
+    /// Sets `register` to `value`, or halts if computing it overflowed.
+    fn store(&mut self, register: Register, value: Option<i32>) {
+        match value {
+            Some(value) => self.registers[register.index()] = value,
+            None => self.overflowed = Some(register),
         }
     }
 }
 
-pub fn puzzle1(path: &str) -> Result<i32, Box<dyn Error>> {
-    let text = fs::read_to_string(path)?;
-    let mut instructions = text.lines().map(Instruction::try_from);
-    let mut computer = Computer::default();
-    let mut sum = 0;
-    for i in 0..=220 {
-        if (i - 20) % 40 == 0 {
-            sum += i * computer.x;
+/// ```
+/// # use advent_of_code_2022::day10::parse_program;
+/// assert_eq!(
+///     parse_program("noop\naddx 1O").err().unwrap().to_string(),
+///     "day 10, line 2, column 6: invalid digit found in string (\"1O\")"
+/// );
+/// assert_eq!(
+///     parse_program("jnz z 1").err().unwrap().to_string(),
+///     "day 10, line 1, column 5: register z not valid, use x or y (\"z\")"
+/// );
+/// assert_eq!(
+///     parse_program("addx 3 3 3").err().unwrap().to_string(),
+///     "day 10, line 1, column 8: unexpected argument 3 (\"3\")"
+/// );
+pub fn parse_program(text: &str) -> Result<Vec<Instruction>, AocError> {
+    input::parse_lines(text, |line| {
+        Instruction::try_from(line).map_err(|err| err.locate(line))
+    })
+}
+
+/// Every error found while assembling a program, in line order.
+#[derive(Debug, thiserror::Error)]
+#[error(
+    "{}",
+    .0.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n")
+)]
+pub struct ProgramErrors(pub Vec<AocError>);
+
+/// Reads a program written by hand. Anything after `#` or `;` is a comment,
+/// blank lines are skipped, and a line can start with labels such as `loop:`
+/// for `jmp` and `jnz` to use in place of an offset. Puzzle input is read
+/// just as [`parse_program`] reads it, but every error is reported rather
+/// than only the first.
+///
+/// ```
+/// # use advent_of_code_2022::day10::{assemble, Instruction, Register};
+/// let program = assemble(
+///     "    addy 3   # count down from 3
+///     loop: addx 2
+///           addy -1
+///           jnz y loop
+///     end:",
+/// )
+/// .unwrap();
+/// assert_eq!(program[3], Instruction::Jnz(Register::Y, -2));
+///
+/// let errors = assemble("a: jmp b\naddx\na: jnz z a\naddx 3 junk").unwrap_err();
+/// assert_eq!(
+///     errors.to_string(),
+///     "day 10, line 1, column 8: label b is not defined (\"b\")
+/// day 10, line 2, column 5: not enough arguments in line to parse instruction (\"addx\")
+/// day 10, line 3, column 1: label a is defined more than once (\"a\")
+/// day 10, line 3, column 8: register z not valid, use x or y (\"z\")
+/// day 10, line 4, column 8: unexpected argument junk (\"junk\")"
+/// );
+/// ```
+pub fn assemble(text: &str) -> Result<Vec<Instruction>, ProgramErrors> {
+    let mut errors = Vec::new();
+    let mut labels = HashMap::new();
+    let mut lines = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let mut code = line.split(['#', ';']).next().unwrap_or_default().trim();
+        while let Some(label) = code
+            .split_whitespace()
+            .next()
+            .and_then(|x| x.strip_suffix(':'))
+        {
+            code = code[label.len() + 1..].trim_start();
+            let error = if !is_label(label) {
+                IPE::InvalidLabel(label.to_string())
+            } else if labels.insert(label, lines.len()).is_some() {
+                IPE::DuplicateLabel(label.to_string())
+            } else {
+                continue;
+            };
+            errors.push(error.locate_in(line, label).offset_lines(i));
         }
-        if computer.cycle_clock() {
-            computer.set_instruction(
-                instructions.next().ok_or("not enough instructions")??,
-            );
+        if !code.is_empty() {
+            lines.push((i, line, code));
         }
     }
-    Ok(sum)
+    let mut program = Vec::new();
+    for (pc, &(i, line, code)) in lines.iter().enumerate() {
+        match assemble_line(code, pc, &labels) {
+            Ok(instruction) => program.push(instruction),
+            Err(err) => errors.push(err.locate_in(line, code).offset_lines(i)),
+        }
+    }
+    if errors.is_empty() {
+        Ok(program)
+    } else {
+        errors.sort_by_key(|err| match err {
+            AocError::Parse { line, column, .. } => (*line, *column),
+            _ => (0, 0),
+        });
+        Err(ProgramErrors(errors))
+    }
 }
 
-pub fn puzzle2(path: &str) -> Result<(), Box<dyn Error>> {
-    let text = fs::read_to_string(path)?;
-    let mut instructions = text.lines().map(Instruction::try_from);
-    let mut computer = Computer::default();
-    for i in 0..=240 {
-        if i != 0 {
-            print!("{}", {
-                if ((i - 1) % 40i32).abs_diff(computer.x) <= 1 {
-                    "@" // # in problem desc. but @ looks nicer
-                } else {
-                    " " // . in problem desc. but space is more legible
-                }
+fn is_label(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Reads the instruction at `pc`, pointing a jump whose last argument is a
+/// label at that label.
+fn assemble_line(
+    code: &str,
+    pc: usize,
+    labels: &HashMap<&str, usize>,
+) -> Result<Instruction, IPE> {
+    let parsed = Instruction::try_from(code);
+    let Some(label) = code
+        .split_whitespace()
+        .skip(1)
+        .last()
+        .filter(|x| is_label(x))
+    else {
+        return parsed;
+    };
+    let placeholder = format!("{}0", &code[..code.len() - label.len()]);
+    let Ok(mut instruction) = Instruction::try_from(placeholder.as_str())
+    else {
+        return parsed;
+    };
+    let Some(offset) = instruction.offset_mut() else {
+        return parsed;
+    };
+    let target = labels
+        .get(label)
+        .ok_or_else(|| IPE::UnknownLabel(label.to_string()))?;
+    *offset = *target as i32 - pc as i32;
+    Ok(instruction)
+}
+
+/// Writes a program one instruction per line, so that [`assemble`] reads it
+/// back unchanged. Every place a jump lands inside the program, or just past
+/// its end, gets a label of its own, numbered in program order.
+///
+/// ```
+/// # use advent_of_code_2022::day10::{assemble, disassemble};
+/// let text = "addy 3\nl1:\naddx 2\naddy -1\njnz y l1\njmp l2\njmp -9\nl2:\n";
+/// let program = assemble(text).unwrap();
+/// assert_eq!(disassemble(&program), text);
+/// assert_eq!(disassemble(&assemble("noop\naddx 3").unwrap()), "noop\naddx 3\n");
+/// ```
+pub fn disassemble(program: &[Instruction]) -> String {
+    let target = |pc: usize, instruction: &Instruction| {
+        let to = pc.checked_add_signed(instruction.offset()? as isize)?;
+        (to <= program.len()).then_some(to)
+    };
+    let mut targets: Vec<_> = program
+        .iter()
+        .enumerate()
+        .filter_map(|(pc, instruction)| target(pc, instruction))
+        .collect();
+    targets.sort_unstable();
+    targets.dedup();
+    let label = |to| {
+        targets
+            .binary_search(&to)
+            .ok()
+            .map(|i| format!("l{}", i + 1))
+    };
+    let mut text = String::new();
+    for pc in 0..=program.len() {
+        if let Some(label) = label(pc) {
+            text += &label;
+            text += ":\n";
+        }
+        let Some(instruction) = program.get(pc) else {
+            break;
+        };
+        let line = match (instruction, target(pc, instruction).and_then(label))
+        {
+            (Instruction::Jnz(r, _), Some(to)) => format!("jnz {r} {to}"),
+            (_, Some(to)) => format!("{} {to}", instruction.mnemonic()),
+            (_, None) => instruction.to_string(),
+        };
+        text += &line;
+        text.push('\n');
+    }
+    text
+}
+
+/// The registers and instruction during one cycle, before the instruction's
+/// effect is seen.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct State {
+    /// The cycle number, counting from 1.
+    pub cycle: u64,
+    pub registers: [i32; Register::ALL.len()],
+    pub pc: usize,
+    pub instruction: Instruction,
+}
+
+impl State {
+    pub fn register(&self, register: Register) -> i32 {
+        self.registers[register.index()]
+    }
+
+    pub fn x(&self) -> i32 {
+        self.register(Register::X)
+    }
+}
+
+/// Names registers as `--break` reads them.
+///
+/// ```
+/// # use advent_of_code_2022::day10::{parse_program, Computer};
+/// let program = parse_program("addx 16").unwrap();
+/// let state = Computer::new(program).state().unwrap();
+/// assert_eq!(
+///     state.to_string(),
+///     "cycle    1  pc   0  x    1  y    0  addx 16"
+/// );
+/// ```
+impl fmt::Display for State {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cycle {:>4}  pc {:>3}  ", self.cycle, self.pc)?;
+        for register in Register::ALL {
+            write!(f, "{register} {:>4}  ", self.register(register))?;
+        }
+        write!(f, "{}", self.instruction)
+    }
+}
+
+/// Iterates over the [`State`] during each cycle of a program.
+///
+/// ```
+/// # use advent_of_code_2022::day10::{parse_program, Computer};
+/// let program = parse_program("noop\naddx 3\naddx -5").unwrap();
+/// let xs: Vec<_> = Computer::new(program).trace().map(|x| x.x()).collect();
+/// assert_eq!(xs, vec![1, 1, 1, 4, 4]);
+/// ```
+pub struct Trace {
+    computer: Computer,
+}
+
+impl Iterator for Trace {
+    type Item = State;
+
+    fn next(&mut self) -> Option<State> {
+        let state = self.computer.state()?;
+        self.computer.tick();
+        Some(state)
+    }
+}
+
+/// A condition to stop a [`Computer`] at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Breakpoint {
+    Cycle(u64),
+    Register(Register, i32),
+}
+
+impl Breakpoint {
+    pub fn is_hit(&self, state: &State) -> bool {
+        match *self {
+            Breakpoint::Cycle(cycle) => state.cycle == cycle,
+            Breakpoint::Register(r, value) => state.register(r) == value,
+        }
+    }
+}
+
+/// Reads `cycle=N`, where cycles count from 1, or a register name and value
+/// such as `x=-3`.
+///
+/// ```
+/// # use advent_of_code_2022::day10::{Breakpoint, Register};
+/// let stop: Breakpoint = "x=-3".parse().unwrap();
+/// assert_eq!(stop, Breakpoint::Register(Register::X, -3));
+/// assert_eq!("cycle=20".parse::<Breakpoint>().unwrap(), Breakpoint::Cycle(20));
+/// assert!("cycle=0".parse::<Breakpoint>().is_err());
+/// assert!("cycle=-3".parse::<Breakpoint>().is_err());
+/// assert_eq!(
+///     "cycle=20 x=1".parse::<Breakpoint>().unwrap_err().to_string(),
+///     "unexpected argument x=1"
+/// );
+/// ```
+impl FromStr for Breakpoint {
+    type Err = InstructionParseError;
+
+    fn from_str(s: &str) -> Result<Breakpoint, IPE> {
+        let (name, value) = s.split_once('=').ok_or(IPE::NotEnoughArguments)?;
+        let mut args = value.split_whitespace();
+        let breakpoint = match name {
+            "cycle" => {
+                let arg = args.next().ok_or(IPE::NotEnoughArguments)?;
+                let cycle = arg.parse::<NonZeroU64>().map_err(|source| {
+                    IPE::ParseIntError {
+                        arg: arg.to_string(),
+                        source,
+                    }
+                })?;
+                Breakpoint::Cycle(cycle.get())
+            }
+            name => Breakpoint::Register(name.try_into()?, number(&mut args)?),
+        };
+        match args.next() {
+            Some(extra) => Err(IPE::ExtraArgument {
+                arg: extra.to_string(),
+                index: s.split_whitespace().count() - args.count() - 1,
+            }),
+            None => Ok(breakpoint),
+        }
+    }
+}
+
+fn not_enough_instructions() -> AocError {
+    AocError::puzzle(DAY, "not enough instructions")
+}
+
+/// The states during the first `cycles` cycles of `program`, failing if it
+/// halts before then.
+pub fn run_cycles(
+    program: &[Instruction],
+    cycles: usize,
+) -> Result<Vec<State>, AocError> {
+    let mut trace = Computer::new(program.to_vec()).trace();
+    let states: Vec<_> = trace.by_ref().take(cycles).collect();
+    if states.len() < cycles {
+        return Err(trace
+            .computer
+            .overflow()
+            .unwrap_or_else(not_enough_instructions));
+    }
+    Ok(states)
+}
+
+pub fn signal_strength(program: &[Instruction]) -> Result<i64, AocError> {
+    Ok(run_cycles(program, 220)?
+        .iter()
+        .filter(|state| state.cycle % 40 == 20)
+        .map(|state| state.cycle as i64 * i64::from(state.x()))
+        .sum())
+}
+
+/// The 40 by 6 screen the program draws on, one bit per pixel.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Crt {
+    rows: [u64; Crt::HEIGHT],
+}
+
+impl Crt {
+    pub const WIDTH: usize = 40;
+    pub const HEIGHT: usize = 6;
+    /// `#` in the problem description, but `@` looks nicer.
+    pub const ON: char = '@';
+    /// `.` in the problem description, but a space is more legible.
+    pub const OFF: char = ' ';
+
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        assert!(x < Crt::WIDTH, "the screen is {} pixels wide", Crt::WIDTH);
+        self.rows[y] & (1 << x) != 0
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, on: bool) {
+        assert!(x < Crt::WIDTH, "the screen is {} pixels wide", Crt::WIDTH);
+        if on {
+            self.rows[y] |= 1 << x;
+        } else {
+            self.rows[y] &= !(1 << x);
+        }
+    }
+
+    /// Draws the screen with `on` and `off` for lit and dark pixels, rather
+    /// than [`Crt::ON`] and [`Crt::OFF`].
+    ///
+    /// ```
+    /// # use advent_of_code_2022::day10::Crt;
+    /// let mut crt = Crt::default();
+    /// crt.set(1, 0, true);
+    /// let text = crt.with_glyphs('#', '.').to_string();
+    /// assert!(text.starts_with(".#......"));
+    /// assert_eq!(text.lines().count(), 6);
+    /// ```
+    pub fn with_glyphs(&self, on: char, off: char) -> Glyphs<'_> {
+        Glyphs { crt: self, on, off }
+    }
+}
+
+/// The capital letters the puzzles draw, in the order [`FONT`] shows them.
+const FONT_LETTERS: &str = "ABCEFGHIJKLOPRSUZ";
+
+/// Each letter is 4 pixels wide and 6 tall, with a blank column after it, as
+/// on the screen.
+const FONT: [&str; 6] = [
+    ".##..###...##..####.####..##..#..#..###...##.#..#.#.....##..###..###...###.#..#.####",
+    "#..#.#..#.#..#.#....#....#..#.#..#...#.....#.#.#..#....#..#.#..#.#..#.#....#..#....#",
+    "#..#.###..#....###..###..#....####...#.....#.##...#....#..#.#..#.#..#.#....#..#...#.",
+    "####.#..#.#....#....#....#.##.#..#...#.....#.#.#..#....#..#.###..###...##..#..#..#..",
+    "#..#.#..#.#..#.#....#....#..#.#..#...#..#..#.#.#..#....#..#.#....#.#.....#.#..#.#...",
+    "#..#.###...##..####.#.....###.#..#..###..##..#..#.####..##..#....#..#.###...##..####",
+];
+
+/// The width of a letter and the gap after it.
+const GLYPH_WIDTH: usize = 5;
+
+/// Packs the 4 by 6 pixels of the glyph starting at column `x` into a
+/// number, reading them with `lit`.
+fn glyph(x: usize, lit: impl Fn(usize, usize) -> bool) -> u32 {
+    let mut bits = 0;
+    for dy in 0..Crt::HEIGHT {
+        for dx in 0..GLYPH_WIDTH - 1 {
+            bits = bits << 1 | u32::from(lit(x + dx, dy));
+        }
+    }
+    bits
+}
+
+/// The glyphs on a [`Crt`] that don't match any letter. They are numbered
+/// from 0, but the message counts from 1, like a reader would.
+///
+/// ```
+/// # use advent_of_code_2022::day10::UnknownGlyphs;
+/// assert_eq!(
+///     UnknownGlyphs(vec![0, 7]).to_string(),
+///     "unrecognized letters at positions 1, 8"
+/// );
+/// ```
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+#[error(
+    "unrecognized letters at positions {}",
+    .0.iter().map(|x| (x + 1).to_string()).collect::<Vec<_>>().join(", ")
+)]
+pub struct UnknownGlyphs(pub Vec<usize>);
+
+impl Crt {
+    /// Reads the capital letters drawn on the screen in the puzzles' 4 by 6
+    /// font. A glyph with no pixels lit is a space, and spaces after the
+    /// last letter are left out.
+    ///
+    /// ```
+    /// # use advent_of_code_2022::day10::{Crt, UnknownGlyphs};
+    /// let mut crt = Crt::default();
+    /// for y in 0..6 {
+    ///     crt.set(0, y, true);
+    ///     crt.set(10, y, true);
+    /// }
+    /// assert_eq!(crt.read(), Err(UnknownGlyphs(vec![0, 2])));
+    /// for x in [0, 1, 2, 3, 10, 11, 12, 13] {
+    ///     crt.set(x, 5, true);
+    /// }
+    /// assert_eq!(crt.read().unwrap(), "L L");
+    /// assert_eq!(Crt::default().read().unwrap(), "");
+    /// ```
+    pub fn read(&self) -> Result<String, UnknownGlyphs> {
+        let mut text = String::new();
+        let mut unknown = Vec::new();
+        for i in 0..Crt::WIDTH / GLYPH_WIDTH {
+            let pixels = glyph(i * GLYPH_WIDTH, |x, y| self.get(x, y));
+            let letter = FONT_LETTERS.chars().enumerate().find(|&(j, _)| {
+                glyph(j * GLYPH_WIDTH, |x, y| FONT[y].as_bytes()[x] == b'#')
+                    == pixels
             });
-            if i % 40 == 0 {
-                println!();
+            match letter {
+                Some((_, c)) => text.push(c),
+                None if pixels == 0 => text.push(' '),
+                None => unknown.push(i),
             }
         }
+        if unknown.is_empty() {
+            Ok(text.trim_end().to_string())
+        } else {
+            Err(UnknownGlyphs(unknown))
+        }
+    }
+}
 
-        if i < 240 && computer.cycle_clock() {
-            computer.set_instruction(
-                instructions.next().ok_or("not enough instructions")??,
-            );
+/// Shows a [`Crt`] with chosen glyphs, one line per row.
+pub struct Glyphs<'a> {
+    crt: &'a Crt,
+    on: char,
+    off: char,
+}
+
+impl fmt::Display for Glyphs<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for y in 0..Crt::HEIGHT {
+            for x in 0..Crt::WIDTH {
+                let on = self.crt.get(x, y);
+                write!(f, "{}", if on { self.on } else { self.off })?;
+            }
+            writeln!(f)?;
         }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Crt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.with_glyphs(Crt::ON, Crt::OFF).fmt(f)
+    }
+}
+
+pub fn draw_crt(program: &[Instruction]) -> Result<Crt, AocError> {
+    let mut crt = Crt::default();
+    for (i, state) in run_cycles(program, Crt::WIDTH * Crt::HEIGHT)?
+        .iter()
+        .enumerate()
+    {
+        let (x, y) = (i % Crt::WIDTH, i / Crt::WIDTH);
+        crt.set(x, y, (x as i32).abs_diff(state.x()) <= 1);
+    }
+    Ok(crt)
+}
+
+pub fn solve_part1(input: &str) -> Result<i64, AocError> {
+    signal_strength(&parse_program(input)?)
+}
+
+pub fn solve_part2(input: &str) -> Result<Crt, AocError> {
+    draw_crt(&parse_program(input)?)
+}
+
+/// Reads the letters on the screen, showing the screen in the error if any
+/// can't be read so that a human can.
+pub fn read_crt(crt: &Crt) -> Result<String, AocError> {
+    crt.read()
+        .map_err(|err| AocError::puzzle(DAY, format!("{err}\n{crt}")))
+}
+
+/// Why a screen can't be drawn by [`synthesize`].
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum DrawError {
+    #[error("letter {0:?} is not in the font, use one of {FONT_LETTERS}")]
+    UnknownLetter(char),
+    #[error("{0} letters don't fit on the screen, the most is 8")]
+    TooManyLetters(usize),
+    #[error("the top left two pixels are always lit, as x starts at 1")]
+    FirstPixelsDark,
+    #[error("the program draws a different screen")]
+    Mismatch,
+}
+
+impl Crt {
+    /// Reads a screen drawn with `#` or `@` for lit pixels and `.` or a
+    /// space for dark ones, as [`Crt`] and the puzzle print it.
+    ///
+    /// ```
+    /// # use advent_of_code_2022::day10::{puzzle2, Crt};
+    /// let crt = puzzle2("day10.txt").unwrap();
+    /// assert_eq!(Crt::parse(&crt.to_string()).unwrap(), crt);
+    /// assert_eq!(
+    ///     Crt::parse("#.\n.#").unwrap_err().to_string(),
+    ///     "day 10: expected a screen 40 pixels wide and 6 tall"
+    /// );
+    /// ```
+    pub fn parse(text: &str) -> Result<Crt, AocError> {
+        let pixels = Grid::parse(DAY, text, |c| match c {
+            '#' | '@' => Some(true),
+            '.' | ' ' => Some(false),
+            _ => None,
+        })?;
+        if (pixels.width(), pixels.height()) != (Crt::WIDTH, Crt::HEIGHT) {
+            return Err(AocError::puzzle(
+                DAY,
+                format!(
+                    "expected a screen {} pixels wide and {} tall",
+                    Crt::WIDTH,
+                    Crt::HEIGHT
+                ),
+            ));
+        }
+        let mut crt = Crt::default();
+        for (x, y) in pixels.cells() {
+            crt.set(x, y, pixels[(x, y)]);
+        }
+        Ok(crt)
+    }
+
+    /// Draws `text` in the puzzles' font, the inverse of [`Crt::read`]. A
+    /// space leaves its glyph dark.
+    ///
+    /// ```
+    /// # use advent_of_code_2022::day10::{Crt, DrawError};
+    /// assert_eq!(Crt::from_letters("ZGCJZJFL").unwrap().read().unwrap(), "ZGCJZJFL");
+    /// assert_eq!(Crt::from_letters("BU ZZ").unwrap().read().unwrap(), "BU ZZ");
+    /// assert_eq!(Crt::from_letters("AB1"), Err(DrawError::UnknownLetter('1')));
+    /// ```
+    pub fn from_letters(text: &str) -> Result<Crt, DrawError> {
+        let count = text.chars().count();
+        if count > Crt::WIDTH / GLYPH_WIDTH {
+            return Err(DrawError::TooManyLetters(count));
+        }
+        let mut crt = Crt::default();
+        for (i, letter) in text.chars().enumerate() {
+            if letter == ' ' {
+                continue;
+            }
+            let j = FONT_LETTERS
+                .find(letter)
+                .ok_or(DrawError::UnknownLetter(letter))?;
+            for (y, row) in FONT.iter().enumerate() {
+                for dx in 0..GLYPH_WIDTH - 1 {
+                    let lit = row.as_bytes()[j * GLYPH_WIDTH + dx] == b'#';
+                    crt.set(i * GLYPH_WIDTH + dx, y, lit);
+                }
+            }
+        }
+        Ok(crt)
+    }
+}
+
+/// Writes an `addx` and `noop` program that draws `crt`, checking it by
+/// running it. Each `addx` takes two cycles, so `x` is set once for every
+/// pair of pixels, which is always enough to light one, both or neither.
+/// The first pair is drawn before any instruction can finish, though, so it
+/// must be lit.
+///
+/// ```
+/// # use advent_of_code_2022::day10::{draw_crt, synthesize, Crt, DrawError};
+/// let crt = Crt::from_letters("BEFPRZ").unwrap();
+/// let program = synthesize(&crt).unwrap();
+/// assert_eq!(draw_crt(&program).unwrap(), crt);
+/// let crt = Crt::from_letters("ABC").unwrap();
+/// assert_eq!(synthesize(&crt), Err(DrawError::FirstPixelsDark));
+/// ```
+pub fn synthesize(crt: &Crt) -> Result<Vec<Instruction>, DrawError> {
+    let mut xs = Vec::new();
+    let mut x = 1;
+    for i in (0..Crt::WIDTH * Crt::HEIGHT).step_by(2) {
+        let (column, y) = (i % Crt::WIDTH, i / Crt::WIDTH);
+        let pair = (crt.get(column, y), crt.get(column + 1, y));
+        let column = column as i32;
+        let draws = |x: i32| {
+            pair == (column.abs_diff(x) <= 1, (column + 1).abs_diff(x) <= 1)
+        };
+        if !draws(x) {
+            if i == 0 {
+                return Err(DrawError::FirstPixelsDark);
+            }
+            x = match pair {
+                (true, true) => column,
+                (true, false) => column - 1,
+                (false, true) => column + 2,
+                (false, false) => column - 2,
+            };
+        }
+        xs.push(x);
+    }
+    let mut program = Vec::new();
+    for (i, &x) in xs.iter().enumerate() {
+        match xs.get(i + 1) {
+            Some(&next) if next != x => {
+                program.push(Instruction::Add(Register::X, next - x));
+            }
+            _ => program.extend([Instruction::Noop; 2]),
+        }
+    }
+    if draw_crt(&program).ok() != Some(*crt) {
+        return Err(DrawError::Mismatch);
+    }
+    Ok(program)
+}
+
+pub fn puzzle1(path: &str) -> Result<i64, AocError> {
+    solve_part1(&fs::read_to_string(path)?)
+}
+
+/// ```
+/// # use advent_of_code_2022::day10::puzzle2;
+/// let crt = puzzle2("day10.txt").unwrap();
+/// assert_eq!(
+///     crt.with_glyphs('#', '.').to_string().lines().next(),
+///     Some("####..##...##....##.####...##.####.#....")
+/// );
+/// assert_eq!(crt.read().unwrap(), "ZGCJZJFL");
+pub fn puzzle2(path: &str) -> Result<Crt, AocError> {
+    solve_part2(&fs::read_to_string(path)?)
+}
+
+pub struct Day10 {
+    program: Vec<Instruction>,
+}
+
+impl Solution for Day10 {
+    fn parse(input: &str) -> Result<Self, AocError> {
+        Ok(Day10 {
+            program: parse_program(input)?,
+        })
+    }
+
+    fn part1(&self) -> Result<Answer, AocError> {
+        Ok(signal_strength(&self.program)?.into())
+    }
+
+    fn part2(&self) -> Result<Answer, AocError> {
+        Ok(read_crt(&draw_crt(&self.program)?)?.into())
     }
-    Ok(())
 }
@@ -1,10 +1,314 @@
+use crate::{input, Answer, AocError, Solution};
 use itertools::Itertools;
-use std::{error::Error, fs, str::FromStr};
+use num_bigint::BigInt;
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+    fs,
+    iter::Peekable,
+    str::FromStr,
+};
 
-pub struct Monkey {
+const DAY: u8 = 11;
+
+/// A way of storing worry levels. Arithmetic returns `None` rather than
+/// overflowing, so a level that grows too large is reported instead of
+/// silently wrapping.
+pub trait WorryLevel: Clone + fmt::Debug + fmt::Display + PartialEq {
+    fn from_i64(level: i64) -> Self;
+    fn add(&self, other: &Self) -> Option<Self>;
+    fn mul(&self, other: &Self) -> Option<Self>;
+    #[must_use]
+    fn div(&self, divisor: i64) -> Self;
+    /// The remainder after dividing by a positive `divisor`.
+    fn rem(&self, divisor: i64) -> i64;
+}
+
+/// Fast, but levels can only reach [`i64::MAX`].
+impl WorryLevel for i64 {
+    fn from_i64(level: i64) -> i64 {
+        level
+    }
+
+    fn add(&self, other: &i64) -> Option<i64> {
+        self.checked_add(*other)
+    }
+
+    fn mul(&self, other: &i64) -> Option<i64> {
+        self.checked_mul(*other)
+    }
+
+    fn div(&self, divisor: i64) -> i64 {
+        self / divisor
+    }
+
+    fn rem(&self, divisor: i64) -> i64 {
+        self.rem_euclid(divisor)
+    }
+}
+
+/// Never overflows, but grows slower as levels get longer.
+impl WorryLevel for BigInt {
+    fn from_i64(level: i64) -> BigInt {
+        level.into()
+    }
+
+    fn add(&self, other: &BigInt) -> Option<BigInt> {
+        Some(self + other)
+    }
+
+    fn mul(&self, other: &BigInt) -> Option<BigInt> {
+        Some(self * other)
+    }
+
+    fn div(&self, divisor: i64) -> BigInt {
+        self / divisor
+    }
+
+    fn rem(&self, divisor: i64) -> i64 {
+        i64::try_from(self % divisor)
+            .expect("a remainder is smaller than its divisor")
+            .rem_euclid(divisor)
+    }
+}
+
+/// Which [`WorryLevel`] to play with, and whether to keep levels small.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Backend {
+    /// `i64` levels kept below the least common multiple of every monkey's
+    /// divisor, which leaves every test unchanged. Dividing a level doesn't commute with
+    /// this, so it needs a relief of 1.
+    #[default]
+    Modulo,
+    /// `i64` levels that are never reduced, failing if one overflows.
+    Checked,
+    /// Levels of any size that are never reduced.
+    Big,
+    /// Each item played on its own as an [`Item`], skipping ahead once it
+    /// repeats itself. Exact, but only without relief.
+    Residues,
+}
+
+/// Reads `modulo`, `checked`, `big` or `residues`.
+impl FromStr for Backend {
+    type Err = UnknownBackend;
+
+    fn from_str(s: &str) -> Result<Backend, UnknownBackend> {
+        match s {
+            "modulo" => Ok(Backend::Modulo),
+            "checked" => Ok(Backend::Checked),
+            "big" => Ok(Backend::Big),
+            "residues" => Ok(Backend::Residues),
+            _ => Err(UnknownBackend),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+#[error("expected modulo, checked, big or residues")]
+pub struct UnknownBackend;
+
+/// How a monkey changes an item's worry level, as an expression of the old
+/// level using `+`, `*`, numbers and parentheses.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Operation {
+    Old,
+    Number(i64),
+    Add(Box<Operation>, Box<Operation>),
+    Mul(Box<Operation>, Box<Operation>),
+}
+
+impl Operation {
+    /// The new worry level, or `None` if it overflows.
+    ///
+    /// ```
+    /// # use advent_of_code_2022::day11::Operation;
+    /// let operation: Operation = "old * 2 + (old + 1) * 3".parse().unwrap();
+    /// assert_eq!(operation.apply(&5), Some(28));
+    /// assert_eq!(operation.apply(&i64::MAX), None);
+    /// ```
+    pub fn apply<W: WorryLevel>(&self, old: &W) -> Option<W> {
+        match self {
+            Operation::Old => Some(old.clone()),
+            Operation::Number(n) => Some(W::from_i64(*n)),
+            Operation::Add(a, b) => a.apply(old)?.add(&b.apply(old)?),
+            Operation::Mul(a, b) => a.apply(old)?.mul(&b.apply(old)?),
+        }
+    }
+
+    /// How tightly the expression binds, so [`Display`] knows where
+    /// parentheses are needed.
+    fn precedence(&self) -> u8 {
+        match self {
+            Operation::Add(..) => 1,
+            Operation::Mul(..) => 2,
+            Operation::Old | Operation::Number(_) => 3,
+        }
+    }
+}
+
+/// Writes the expression with only the parentheses it needs, so that it
+/// parses back to the same tree.
+///
+/// ```
+/// # use advent_of_code_2022::day11::Operation;
+/// for text in ["old * old", "old * 19 + 3", "(old + 1) * old", "old + (1 + old)"] {
+///     let operation: Operation = text.parse().unwrap();
+///     assert_eq!(operation.to_string(), text);
+/// }
+/// assert_eq!("((old)) * (2)".parse::<Operation>().unwrap().to_string(), "old * 2");
+/// ```
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (op, a, b) = match self {
+            Operation::Old => return write!(f, "old"),
+            Operation::Number(n) => return write!(f, "{n}"),
+            Operation::Add(a, b) => ('+', a, b),
+            Operation::Mul(a, b) => ('*', a, b),
+        };
+        // Both operators group to the left, so a right operand of the same
+        // precedence needs parentheses too.
+        if a.precedence() < self.precedence() {
+            write!(f, "({a})")?;
+        } else {
+            write!(f, "{a}")?;
+        }
+        write!(f, " {op} ")?;
+        if b.precedence() <= self.precedence() {
+            write!(f, "({b})")
+        } else {
+            write!(f, "{b}")
+        }
+    }
+}
+
+/// Reads an expression such as `old * 19` or `(old + 2) * old`, with `*`
+/// binding tighter than `+`.
+///
+/// ```
+/// # use advent_of_code_2022::day11::Operation;
+/// assert_eq!(
+///     "old * old".parse::<Operation>().unwrap(),
+///     Operation::Mul(Box::new(Operation::Old), Box::new(Operation::Old))
+/// );
+/// assert_eq!(
+///     "old * (3".parse::<Operation>().unwrap_err().to_string(),
+///     "day 11, line 1, column 9: expected ) (\"old * (3\")"
+/// );
+/// ```
+impl FromStr for Operation {
+    type Err = AocError;
+
+    fn from_str(s: &str) -> Result<Operation, AocError> {
+        parse_expression(s, s)
+    }
+}
+
+/// Splits an expression into numbers, words and single character symbols,
+/// each a slice of the text so that errors can point at them.
+fn tokens(text: &str) -> impl Iterator<Item = &str> {
+    let mut rest = text.trim_start();
+    std::iter::from_fn(move || {
+        let first = rest.chars().next()?;
+        let len = if first.is_alphanumeric() {
+            rest.find(|c: char| !c.is_alphanumeric())
+                .unwrap_or(rest.len())
+        } else {
+            first.len_utf8()
+        };
+        let (token, tail) = rest.split_at(len);
+        rest = tail.trim_start();
+        Some(token)
+    })
+}
+
+/// Parses `expression`, a slice of `line`, reporting errors against the line.
+fn parse_expression(
+    line: &str,
+    expression: &str,
+) -> Result<Operation, AocError> {
+    let mut tokens = tokens(expression).peekable();
+    let operation = parse_sum(line, &mut tokens)?;
+    match tokens.next() {
+        Some(token) => {
+            Err(AocError::parse(DAY, line, token, "expected + or *"))
+        }
+        None => Ok(operation),
+    }
+}
+
+fn parse_sum<'a>(
+    line: &str,
+    tokens: &mut Peekable<impl Iterator<Item = &'a str>>,
+) -> Result<Operation, AocError> {
+    let mut sum = parse_product(line, tokens)?;
+    while tokens.next_if_eq(&"+").is_some() {
+        let b = parse_product(line, tokens)?;
+        sum = Operation::Add(Box::new(sum), Box::new(b));
+    }
+    Ok(sum)
+}
+
+fn parse_product<'a>(
+    line: &str,
+    tokens: &mut Peekable<impl Iterator<Item = &'a str>>,
+) -> Result<Operation, AocError> {
+    let mut product = parse_term(line, tokens)?;
+    while tokens.next_if_eq(&"*").is_some() {
+        let b = parse_term(line, tokens)?;
+        product = Operation::Mul(Box::new(product), Box::new(b));
+    }
+    Ok(product)
+}
+
+fn parse_term<'a>(
+    line: &str,
+    tokens: &mut Peekable<impl Iterator<Item = &'a str>>,
+) -> Result<Operation, AocError> {
+    let token = tokens.next().unwrap_or_default();
+    match token {
+        "old" => Ok(Operation::Old),
+        "(" => {
+            let operation = parse_sum(line, tokens)?;
+            match tokens.next() {
+                Some(")") => Ok(operation),
+                token => Err(AocError::parse(
+                    DAY,
+                    line,
+                    token.unwrap_or_default(),
+                    "expected )",
+                )),
+            }
+        }
+        x if x.starts_with(|c: char| c.is_ascii_digit()) => {
+            x.parse().map(Operation::Number).map_err(|_| {
+                AocError::parse(DAY, line, x, "expected a number or old")
+            })
+        }
+        x => Err(AocError::parse(DAY, line, x, "expected old, a number or (")),
+    }
+}
+
+/// How closely notes must follow the puzzle's wording.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Strictness {
+    /// Every line must start with its label, such as `Test: divisible by`,
+    /// and hold nothing but its value.
+    Strict,
+    /// Labels are ignored and each line only needs the numbers it should
+    /// hold, or an operation after `=`. Lines past the last field are still
+    /// an error.
+    #[default]
+    Lenient,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Monkey<W = i64> {
     pub id: usize,
-    pub items: Vec<i64>,
-    pub operation: Box<dyn Fn(i64) -> i64>,
+    pub items: Vec<W>,
+    pub operation: Operation,
     pub test_divisor: i64,
     pub true_monkey: usize,
     pub false_monkey: usize,
@@ -17,7 +321,7 @@ impl Monkey {
     /// let monkey1 = Monkey {
     ///     id: 0,
     ///     items: vec![84, 72, 58, 51],
-    ///     operation: Box::new(|x| x * 3),
+    ///     operation: "old * 3".parse().unwrap(),
     ///     test_divisor: 13,
     ///     true_monkey: 1,
     ///     false_monkey: 7,
@@ -32,63 +336,96 @@ impl Monkey {
     ///   If false: throw to monkey 7";
     /// let monkey2 = Monkey::from(text).unwrap();
     ///
-    /// assert_eq!(monkey1.id, monkey2.id);
-    /// assert_eq!(monkey1.items, monkey2.items);
-    /// assert_eq!(monkey1.test_divisor, monkey2.test_divisor);
-    /// assert_eq!(monkey1.true_monkey, monkey2.true_monkey);
-    /// assert_eq!(monkey1.false_monkey, monkey2.false_monkey);
-    /// assert_eq!(monkey1.inspect(6), monkey2.inspect(6));
-    /// assert_eq!(monkey1.inspect(6), 18);
-    pub fn from(text: &str) -> Result<Monkey, Box<dyn Error>> {
-        let mut lines = text.lines();
-        let err = "not enough lines";
-        let id = find_int(lines.next().ok_or(err)?)?;
-        let items: Vec<i64> = lines
-            .next()
-            .ok_or(err)?
-            .split(|c: char| !c.is_numeric())
-            .filter(|s| !s.is_empty())
-            .map(str::parse)
-            .map(Result::unwrap)
-            .collect();
-        let operation = {
-            match lines
-                .next()
-                .ok_or(err)?
-                .split('=')
-                .last()
-                .ok_or("no operation")?
-                .split_whitespace()
-                .filter(|s| !s.is_empty())
-                .skip(1)
-                .take(2)
-                .collect_tuple::<(_, _)>()
-                .ok_or("not enough arguments in operation")?
-            {
-                ("*", "old") => {
-                    let f: Box<dyn Fn(i64) -> i64> = Box::new(|x| x * x);
-                    f
-                }
-                ("+", "old") => {
-                    let f: Box<dyn Fn(i64) -> i64> = Box::new(|x| x + x);
-                    f
-                }
-                ("*", num) => {
-                    let z: i64 = num.parse()?;
-                    let f: Box<dyn Fn(i64) -> i64> = Box::new(move |x| x * z);
-                    f
-                }
-                ("+", num) => {
-                    let z: i64 = num.parse()?;
-                    let f: Box<dyn Fn(i64) -> i64> = Box::new(move |x| x + z);
-                    f
-                }
-                (..) => None.ok_or("not a valid operation")?,
+    /// assert_eq!(monkey1, monkey2);
+    /// assert_eq!(monkey1.inspect(&6), Some(18));
+    pub fn from(text: &str) -> Result<Monkey, AocError> {
+        Monkey::parse(text, Strictness::Lenient)
+    }
+
+    /// Reads one monkey's notes. This doesn't check where it throws items,
+    /// as that depends on the other monkeys, so see [`parse_monkeys_with`].
+    ///
+    /// ```
+    /// # use advent_of_code_2022::day11::{Monkey, Strictness};
+    /// let text = "Monkey 0:
+    ///   Starting items: 84, 72
+    ///   Operation: new = old * 3
+    ///   Test: divisible by 13
+    ///     If true: throw to monkey 1
+    ///     If false: throw to monkey 7";
+    /// let monkey = Monkey::parse(text, Strictness::Strict).unwrap();
+    /// assert_eq!(monkey.to_string(), text);
+    /// let text = text.replace("Test", "Text");
+    /// assert_eq!(
+    ///     Monkey::parse(&text, Strictness::Strict).unwrap_err().to_string(),
+    ///     "day 11, line 4, column 3: expected \"Test: divisible by\" \
+    ///      (\"Text: divisible by 13\")"
+    /// );
+    /// assert_eq!(Monkey::parse(&text, Strictness::Lenient).unwrap(), monkey);
+    /// let text = format!("{text}\nextra junk line");
+    /// assert_eq!(
+    ///     Monkey::parse(&text, Strictness::Lenient).unwrap_err().to_string(),
+    ///     "day 11, line 7, column 1: expected a blank line before the next \
+    ///      monkey (\"extra junk line\")"
+    /// );
+    /// ```
+    pub fn parse(
+        text: &str,
+        strictness: Strictness,
+    ) -> Result<Monkey, AocError> {
+        if strictness == Strictness::Lenient {
+            return parse_lenient(text);
+        }
+        let lines = &mut text.lines().enumerate();
+        let id = next_line(text, lines, |line| {
+            let value = field(line, "Monkey")?;
+            let Some(id) = value.strip_suffix(':') else {
+                return Err(AocError::parse(DAY, line, value, "expected :"));
+            };
+            number(line, id)
+        })?;
+        let items = next_line(text, lines, |line| {
+            let value = field(line, "Starting items:")?;
+            if value.is_empty() {
+                return Ok(Vec::new());
             }
-        };
-        let test_divisor = find_int(lines.next().ok_or(err)?)?;
-        let true_monkey = find_int(lines.next().ok_or(err)?)?;
-        let false_monkey = find_int(lines.next().ok_or(err)?)?;
+            value
+                .split(',')
+                .map(|item| {
+                    let item = item.trim();
+                    item.parse().map_err(|_| {
+                        AocError::parse(
+                            DAY,
+                            line,
+                            item,
+                            "expected a worry level",
+                        )
+                    })
+                })
+                .collect()
+        })?;
+        let operation = next_line(text, lines, |line| {
+            parse_expression(line, field(line, "Operation: new =")?)
+        })?;
+        let test_divisor = next_line(text, lines, |line| {
+            let value = field(line, "Test: divisible by")?;
+            match number(line, value)? {
+                x if x > 0 => Ok(x),
+                _ => Err(AocError::parse(
+                    DAY,
+                    line,
+                    value,
+                    "expected a divisor above 0",
+                )),
+            }
+        })?;
+        let true_monkey = next_line(text, lines, |line| {
+            number(line, field(line, "If true: throw to monkey")?)
+        })?;
+        let false_monkey = next_line(text, lines, |line| {
+            number(line, field(line, "If false: throw to monkey")?)
+        })?;
+        end_of_notes(lines)?;
         Ok(Monkey {
             id,
             items,
@@ -100,89 +437,814 @@ impl Monkey {
         })
     }
 
-    pub fn get_destination(&self, item: i64) -> usize {
-        if item % self.test_divisor == 0 {
+    /// The same monkey holding its items as another kind of [`WorryLevel`].
+    pub fn convert<W: WorryLevel>(&self) -> Monkey<W> {
+        Monkey {
+            id: self.id,
+            items: self.items.iter().map(|&x| W::from_i64(x)).collect(),
+            operation: self.operation.clone(),
+            test_divisor: self.test_divisor,
+            true_monkey: self.true_monkey,
+            false_monkey: self.false_monkey,
+            inspections: self.inspections,
+        }
+    }
+}
+
+impl<W: WorryLevel> Monkey<W> {
+    pub fn get_destination(&self, item: &W) -> usize {
+        if item.rem(self.test_divisor) == 0 {
             self.true_monkey
         } else {
             self.false_monkey
         }
     }
 
-    pub fn inspect(&self, item: i64) -> i64 {
-        (self.operation)(item)
+    /// The item's new worry level, or `None` if it overflows.
+    pub fn inspect(&self, item: &W) -> Option<W> {
+        self.operation.apply(item)
+    }
+}
+
+fn parse_lenient(text: &str) -> Result<Monkey, AocError> {
+    let lines = &mut text.lines().enumerate();
+    let id = next_line(text, lines, find_int)?;
+    let items = next_line(text, lines, parse_items)?;
+    let operation = next_line(text, lines, parse_operation)?;
+    let test_divisor = next_line(text, lines, |line| match find_int(line)? {
+        0 => Err(AocError::parse(DAY, line, line, "can't divide by 0")),
+        x => Ok(x),
+    })?;
+    let true_monkey = next_line(text, lines, find_int)?;
+    let false_monkey = next_line(text, lines, find_int)?;
+    end_of_notes(lines)?;
+    Ok(Monkey {
+        id,
+        items,
+        operation,
+        test_divisor,
+        true_monkey,
+        false_monkey,
+        inspections: 0,
+    })
+}
+
+/// The value after `label` on a line of a monkey's notes, which may be
+/// indented.
+fn field<'a>(line: &'a str, label: &str) -> Result<&'a str, AocError> {
+    line.trim_start()
+        .strip_prefix(label)
+        .map(str::trim)
+        .ok_or_else(|| {
+            AocError::parse(
+                DAY,
+                line,
+                line.trim(),
+                format!("expected {label:?}"),
+            )
+        })
+}
+
+/// Reads `value`, a slice of `line`, as a number and nothing else.
+fn number<T: FromStr>(line: &str, value: &str) -> Result<T, AocError> {
+    value
+        .parse()
+        .map_err(|_| AocError::parse(DAY, line, value, "expected a number"))
+}
+
+/// Writes the notes as the puzzle does, so that they parse back unchanged.
+impl<W: Display> fmt::Display for Monkey<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Monkey {}:", self.id)?;
+        write!(f, "  Starting items:")?;
+        if !self.items.is_empty() {
+            write!(f, " {}", self.items.iter().join(", "))?;
+        }
+        writeln!(f)?;
+        writeln!(f, "  Operation: new = {}", self.operation)?;
+        writeln!(f, "  Test: divisible by {}", self.test_divisor)?;
+        writeln!(f, "    If true: throw to monkey {}", self.true_monkey)?;
+        write!(f, "    If false: throw to monkey {}", self.false_monkey)
+    }
+}
+
+/// Checks that a monkey's notes have no lines after the last field.
+fn end_of_notes<'a>(
+    lines: &mut impl Iterator<Item = (usize, &'a str)>,
+) -> Result<(), AocError> {
+    match lines.next() {
+        Some((i, line)) => Err(AocError::parse(
+            DAY,
+            line,
+            line,
+            "expected a blank line before the next monkey",
+        )
+        .offset_lines(i)),
+        None => Ok(()),
     }
 }
 
+/// Parses the next line of a monkey's notes, numbering errors by the line
+/// within `text`.
+fn next_line<'a, T>(
+    text: &str,
+    lines: &mut impl Iterator<Item = (usize, &'a str)>,
+    parse: impl FnOnce(&'a str) -> Result<T, AocError>,
+) -> Result<T, AocError> {
+    let Some((i, line)) = lines.next() else {
+        return Err(AocError::parse(DAY, "", "", "expected another line")
+            .offset_lines(text.lines().count()));
+    };
+    parse(line).map_err(|err| err.offset_lines(i))
+}
+
+fn parse_items(line: &str) -> Result<Vec<i64>, AocError> {
+    line.split(|c: char| !c.is_numeric())
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            s.parse().map_err(|_| {
+                AocError::parse(DAY, line, s, "expected a worry level")
+            })
+        })
+        .collect()
+}
+
+fn parse_operation(line: &str) -> Result<Operation, AocError> {
+    let Some((_, expression)) = line.split_once('=') else {
+        return Err(AocError::parse(
+            DAY,
+            line,
+            "",
+            "expected an operation like new = old * 3",
+        ));
+    };
+    parse_expression(line, expression)
+}
+
 /// ```
 /// # use advent_of_code_2022::day11::find_int;
 /// assert_eq!(find_int::<i32>("fweufwe334fewufw1").unwrap(), 334);
 /// assert_eq!(find_int::<i32>("123").unwrap(), 123);
 /// assert_eq!(find_int::<i32>("I am 1.0 years old").unwrap(), 1);
-pub fn find_int<T>(line: &str) -> Result<T, Box<dyn Error>>
+pub fn find_int<T>(line: &str) -> Result<T, AocError>
 where
     T: FromStr,
-    <T as FromStr>::Err: Error + 'static,
+    <T as FromStr>::Err: Display,
 {
-    Ok(line
-        .split(|c: char| !c.is_numeric())
+    let num = first_number(line);
+    if num.is_empty() {
+        return Err(AocError::parse(DAY, line, "", "no number found"));
+    }
+    num.parse::<T>()
+        .map_err(|err| AocError::parse(DAY, line, num, err.to_string()))
+}
+
+/// The first run of digits in `line`, or an empty string if there is none.
+fn first_number(line: &str) -> &str {
+    line.split(|c: char| !c.is_numeric())
         .find(|s| !s.is_empty())
-        .ok_or("no number found")?
-        .parse::<T>()?)
+        .unwrap_or_default()
 }
 
-pub fn round(monkeys: &mut [Monkey], divisor: i64, modulo: i64) {
+/// Plays one round, dividing each new worry level by `relief` and then, if
+/// there is a `modulo`, keeping only its remainder.
+pub fn round<W: WorryLevel>(
+    monkeys: &mut [Monkey<W>],
+    relief: i64,
+    modulo: Option<i64>,
+) -> Result<(), AocError> {
     let mut new_items = vec![vec![]; monkeys.len()];
     for monkey in &mut *monkeys {
         monkey.inspections += monkey.items.len() + new_items[monkey.id].len();
-        for &item in &new_items[monkey.id] {
-            monkey.items.push(item);
-        }
-        new_items[monkey.id] = Vec::new();
-        for &item in &monkey.items {
-            let item = (monkey.inspect(item) % modulo) / divisor;
-            new_items[monkey.get_destination(item)].push(item);
+        monkey.items.append(&mut new_items[monkey.id]);
+        for item in &monkey.items {
+            let Some(worry) = monkey.inspect(item) else {
+                return Err(AocError::puzzle(
+                    DAY,
+                    format!(
+                        "worry level overflowed when monkey {} inspected an \
+                         item at {item:?}",
+                        monkey.id
+                    ),
+                ));
+            };
+            let mut worry = worry.div(relief);
+            if let Some(modulo) = modulo {
+                worry = W::from_i64(worry.rem(modulo));
+            }
+            new_items[monkey.get_destination(&worry)].push(worry);
         }
     }
     for (monkey, items) in monkeys.iter_mut().zip(new_items) {
         monkey.items = items;
     }
+    Ok(())
+}
+
+/// Plays `rounds` rounds as [`round`] does.
+pub fn play<W: WorryLevel>(
+    monkeys: &mut [Monkey<W>],
+    rounds: usize,
+    relief: i64,
+    modulo: Option<i64>,
+) -> Result<(), AocError> {
+    play_observed(monkeys, rounds, relief, modulo, &[], |_| {})
 }
 
+/// Plays `rounds` rounds as [`play`] does, calling `observe` with a
+/// [`Report`] after each round numbered in `after`, counting from 1.
+pub fn play_observed<W: WorryLevel>(
+    monkeys: &mut [Monkey<W>],
+    rounds: usize,
+    relief: i64,
+    modulo: Option<i64>,
+    after: &[usize],
+    mut observe: impl FnMut(Report),
+) -> Result<(), AocError> {
+    for played in 1..=rounds {
+        round(monkeys, relief, modulo)?;
+        if after.contains(&played) {
+            observe(Report::new(played, monkeys));
+        }
+    }
+    Ok(())
+}
+
+/// What the monkeys hold and how many items each has inspected after a
+/// round. Its [`Display`] is the inspection table from part 2 of the puzzle,
+/// and [`Report::holdings`] is the list of items from part 1, so either can
+/// be compared with the puzzle's walkthrough.
+///
 /// ```
-/// # use advent_of_code_2022::day11::puzzle1;
-/// assert_eq!(puzzle1("day11a.txt").unwrap(), 10605);
-pub fn puzzle1(path: &str) -> Result<usize, Box<dyn Error>> {
-    let text = fs::read_to_string(path)?;
-    let mut monkeys: Vec<Monkey> = Vec::new();
-    for monkey in text.split("\n\r\n").map(Monkey::from) {
-        monkeys.push(monkey?);
+/// # use advent_of_code_2022::day11::{inspections, parse_monkeys, Rules};
+/// # use std::fs;
+/// let monkeys = parse_monkeys(&fs::read_to_string("day11a.txt").unwrap()).unwrap();
+/// let mut reports = Vec::new();
+/// inspections(&monkeys, &Rules::PART1, &[1, 20], |x| reports.push(x)).unwrap();
+/// assert_eq!(
+///     reports[0].holdings().to_string(),
+///     "After round 1, the monkeys are holding items with these worry levels:
+/// Monkey 0: 20, 23, 27, 26
+/// Monkey 1: 2080, 25, 167, 207, 401, 1046
+/// Monkey 2: \nMonkey 3: \n"
+/// );
+/// assert_eq!(
+///     reports[1].to_string(),
+///     "== After round 20 ==
+/// Monkey 0 inspected items 101 times.
+/// Monkey 1 inspected items 95 times.
+/// Monkey 2 inspected items 7 times.
+/// Monkey 3 inspected items 105 times.
+/// "
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Report {
+    pub round: usize,
+    /// The worry levels of the items each monkey holds, written out so that
+    /// every [`WorryLevel`] reports the same way.
+    pub items: Vec<Vec<String>>,
+    pub inspections: Vec<usize>,
+}
+
+impl Report {
+    pub fn new<W: Display>(round: usize, monkeys: &[Monkey<W>]) -> Report {
+        Report {
+            round,
+            items: monkeys
+                .iter()
+                .map(|monkey| monkey.items.iter().map(W::to_string).collect())
+                .collect(),
+            inspections: monkeys.iter().map(|x| x.inspections).collect(),
+        }
+    }
+
+    /// The items each monkey holds, as part 1 of the puzzle lists them.
+    pub fn holdings(&self) -> Holdings<'_> {
+        Holdings { report: self }
+    }
+}
+
+/// Shows the items in a [`Report`], one line per monkey.
+pub struct Holdings<'a> {
+    report: &'a Report,
+}
+
+impl fmt::Display for Holdings<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "After round {}, the monkeys are holding items with these worry \
+             levels:",
+            self.report.round
+        )?;
+        for (id, items) in self.report.items.iter().enumerate() {
+            writeln!(f, "Monkey {id}: {}", items.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "== After round {} ==", self.round)?;
+        for (id, count) in self.inspections.iter().enumerate() {
+            writeln!(f, "Monkey {id} inspected items {count} times.")?;
+        }
+        Ok(())
+    }
+}
+
+pub fn parse_monkeys(text: &str) -> Result<Vec<Monkey>, AocError> {
+    parse_monkeys_with(text, Strictness::Lenient)
+}
+
+/// Reads every monkey's notes, checking that the monkeys are numbered in
+/// order from 0 and that each throws only to other monkeys that exist.
+///
+/// ```
+/// # use advent_of_code_2022::day11::{parse_monkeys_with, Strictness};
+/// # use itertools::Itertools;
+/// # use std::fs;
+/// let text = fs::read_to_string("day11a.txt").unwrap();
+/// let monkeys = parse_monkeys_with(&text, Strictness::Strict).unwrap();
+/// assert_eq!(monkeys.iter().join("\n\n"), text.trim_end());
+///
+/// let error = |text: &str| {
+///     parse_monkeys_with(&text, Strictness::Strict).unwrap_err().to_string()
+/// };
+/// assert_eq!(
+///     error(&text.replacen("Monkey 1:", "Monkey 4:", 1)),
+///     "day 11, line 8, column 8: expected monkey 1, as monkeys are \
+///      numbered in order from 0 (\"4\")"
+/// );
+/// assert_eq!(
+///     error(&text.replacen("If true: throw to monkey 2", "If true: throw to monkey 0", 1)),
+///     "day 11, line 5, column 30: a monkey can't throw to itself (\"0\")"
+/// );
+/// assert_eq!(
+///     error(&text.replacen("If false: throw to monkey 3", "If false: throw to monkey 9", 1)),
+///     "day 11, line 6, column 31: there is no monkey 9 (\"9\")"
+/// );
+/// ```
+pub fn parse_monkeys_with(
+    text: &str,
+    strictness: Strictness,
+) -> Result<Vec<Monkey>, AocError> {
+    let sections = input::sections(text);
+    let mut monkeys = Vec::new();
+    for section in &sections {
+        monkeys.push(
+            Monkey::parse(section, strictness).map_err(|err| {
+                err.offset_lines(input::line_of(text, section))
+            })?,
+        );
+    }
+    for (i, (monkey, section)) in monkeys.iter().zip(&sections).enumerate() {
+        // Both parsers read one field per line, so the fields are found by
+        // their line in the section.
+        let error = |field: usize, message: String| {
+            let line = section.lines().nth(field).unwrap_or_default();
+            AocError::parse(DAY, line, first_number(line), message)
+                .offset_lines(input::line_of(text, section) + field)
+        };
+        if monkey.id != i {
+            return Err(error(
+                0,
+                format!(
+                    "expected monkey {i}, as monkeys are numbered in order \
+                     from 0"
+                ),
+            ));
+        }
+        for (field, target) in
+            [(4, monkey.true_monkey), (5, monkey.false_monkey)]
+        {
+            if target == monkey.id {
+                return Err(error(
+                    field,
+                    "a monkey can't throw to itself".to_string(),
+                ));
+            }
+            if target >= monkeys.len() {
+                return Err(error(
+                    field,
+                    format!("there is no monkey {target}"),
+                ));
+            }
+        }
+    }
+    Ok(monkeys)
+}
+
+/// One item followed on its own. Monkeys only look at the item they are
+/// inspecting, so each item can be played separately from the rest. Its
+/// worry level is kept as its remainder by each monkey's divisor, which is
+/// all the tests need and stays small without the items having to share a
+/// modulus.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Item {
+    /// The monkey holding the item.
+    pub monkey: usize,
+    /// The worry level's remainder by each monkey's divisor, in monkey order.
+    pub residues: Vec<i64>,
+}
+
+impl Item {
+    pub fn new(monkeys: &[Monkey], monkey: usize, level: i64) -> Item {
+        Item {
+            monkey,
+            residues: monkeys
+                .iter()
+                .map(|x| level.rem_euclid(x.test_divisor))
+                .collect(),
+        }
     }
-    let modulo = monkeys.iter().map(|x| x.test_divisor).product();
-    for _ in 0..20 {
-        round(&mut monkeys, 3, modulo);
+
+    /// Plays one round for this item, calling `visit` with each monkey that
+    /// inspects it. A monkey throwing it to a later monkey sees it inspected
+    /// again in the same round, as every monkey takes its turn in order.
+    pub fn round(
+        &mut self,
+        monkeys: &[Monkey],
+        mut visit: impl FnMut(usize),
+    ) -> Result<(), AocError> {
+        loop {
+            let monkey = &monkeys[self.monkey];
+            visit(self.monkey);
+            for (residue, divisor) in self
+                .residues
+                .iter_mut()
+                .zip(monkeys.iter().map(|x| x.test_divisor))
+            {
+                *residue = monkey
+                    .inspect(residue)
+                    .ok_or_else(|| {
+                        AocError::puzzle(
+                            DAY,
+                            format!(
+                                "worry level overflowed when monkey {} \
+                                 inspected an item at {residue} modulo \
+                                 {divisor}",
+                                monkey.id
+                            ),
+                        )
+                    })?
+                    .rem_euclid(divisor);
+            }
+            let to = if self.residues[self.monkey] == 0 {
+                monkey.true_monkey
+            } else {
+                monkey.false_monkey
+            };
+            let next_round = to <= self.monkey;
+            self.monkey = to;
+            if next_round {
+                return Ok(());
+            }
+        }
     }
-    Ok(monkeys
+}
+
+/// Every starting item, in the order the notes list them.
+pub fn items(monkeys: &[Monkey]) -> Vec<Item> {
+    monkeys
         .iter()
-        .map(|monkey| monkey.inspections)
-        .sorted_by(|a, b| b.cmp(a))
-        .take(2)
-        .product())
+        .flat_map(|monkey| {
+            monkey
+                .items
+                .iter()
+                .map(|&level| Item::new(monkeys, monkey.id, level))
+        })
+        .collect()
+}
+
+/// The monkeys that inspect the `item`th starting item, counting from 0,
+/// during each of the first `rounds` rounds.
+///
+/// ```
+/// # use advent_of_code_2022::day11::{item_history, parse_monkeys};
+/// # use std::fs;
+/// let monkeys = parse_monkeys(&fs::read_to_string("day11a.txt").unwrap()).unwrap();
+/// // Monkey 0 throws the first item to monkey 3, which throws it to monkey 1
+/// // for the next round.
+/// let history = item_history(&monkeys, 0, 3).unwrap();
+/// assert_eq!(history, vec![vec![0, 3], vec![1], vec![0, 3]]);
+/// ```
+pub fn item_history(
+    monkeys: &[Monkey],
+    item: usize,
+    rounds: usize,
+) -> Result<Vec<Vec<usize>>, AocError> {
+    let Some(mut item) = items(monkeys).into_iter().nth(item) else {
+        return Err(AocError::puzzle(DAY, format!("there is no item {item}")));
+    };
+    (0..rounds)
+        .map(|_| {
+            let mut visits = Vec::new();
+            item.round(monkeys, |monkey| visits.push(monkey))?;
+            Ok(visits)
+        })
+        .collect()
 }
 
-pub fn puzzle2(path: &str) -> Result<usize, Box<dyn Error>> {
-    let text = fs::read_to_string(path)?;
-    let mut monkeys: Vec<Monkey> = Vec::new();
-    for monkey in text.split("\n\r\n").map(Monkey::from) {
-        monkeys.push(monkey?);
+/// How many times each monkey inspects `item` in `rounds` rounds. Each
+/// item has only so many states, so it must end up repeating itself, and
+/// with `skip_cycles` the repeats are counted without being played.
+pub fn item_inspections(
+    monkeys: &[Monkey],
+    item: &Item,
+    rounds: usize,
+    skip_cycles: bool,
+) -> Result<Vec<usize>, AocError> {
+    let mut item = item.clone();
+    let mut counts = vec![0; monkeys.len()];
+    // The round each state was first seen at the start of, and the counts
+    // after every round so far.
+    let mut seen: HashMap<Item, usize> = HashMap::new();
+    let mut totals = vec![counts.clone()];
+    for played in 0..rounds {
+        if skip_cycles {
+            if let Some(&start) = seen.get(&item) {
+                let period = played - start;
+                let (cycles, rest) =
+                    ((rounds - played) / period, (rounds - played) % period);
+                for (monkey, count) in counts.iter_mut().enumerate() {
+                    let per_cycle =
+                        totals[played][monkey] - totals[start][monkey];
+                    *count += cycles * per_cycle + totals[start + rest][monkey]
+                        - totals[start][monkey];
+                }
+                return Ok(counts);
+            }
+            seen.insert(item.clone(), played);
+        }
+        item.round(monkeys, |monkey| counts[monkey] += 1)?;
+        if skip_cycles {
+            totals.push(counts.clone());
+        }
     }
-    let modulo = monkeys.iter().map(|x| x.test_divisor).product();
-    for _ in 0..10_000 {
-        round(&mut monkeys, 1, modulo);
+    Ok(counts)
+}
+
+/// How many times each monkey inspects an item in `rounds` rounds, playing
+/// each item on its own.
+///
+/// ```
+/// # use advent_of_code_2022::day11::{parse_monkeys, residue_inspections};
+/// # use std::fs;
+/// let monkeys = parse_monkeys(&fs::read_to_string("day11a.txt").unwrap()).unwrap();
+/// let inspections = vec![52166, 47830, 1938, 52013];
+/// assert_eq!(residue_inspections(&monkeys, 10_000, false).unwrap(), inspections);
+/// assert_eq!(residue_inspections(&monkeys, 10_000, true).unwrap(), inspections);
+/// let inspections = residue_inspections(&monkeys, 1_000_000_000, true).unwrap();
+/// assert_eq!(inspections[0], 5_217_653_496);
+/// ```
+pub fn residue_inspections(
+    monkeys: &[Monkey],
+    rounds: usize,
+    skip_cycles: bool,
+) -> Result<Vec<usize>, AocError> {
+    let mut counts = vec![0; monkeys.len()];
+    for item in items(monkeys) {
+        let item = item_inspections(monkeys, &item, rounds, skip_cycles)?;
+        for (count, n) in counts.iter_mut().zip(item) {
+            *count += n;
+        }
+    }
+    Ok(counts)
+}
+
+/// The least common multiple of every monkey's divisor, the smallest number
+/// levels can be kept below without changing any test.
+fn common_multiple(monkeys: &[Monkey]) -> Result<i64, AocError> {
+    fn gcd(a: i64, b: i64) -> i64 {
+        if b == 0 {
+            a
+        } else {
+            gcd(b, a % b)
+        }
     }
-    Ok(monkeys
+    monkeys
+        .iter()
+        .try_fold(1_i64, |lcm, monkey| {
+            lcm.checked_mul(monkey.test_divisor / gcd(lcm, monkey.test_divisor))
+        })
+        .ok_or_else(|| {
+            AocError::puzzle(
+                DAY,
+                "the divisors have no common multiple that fits in 64 bits, so \
+                 keep worry levels as big or residues instead",
+            )
+        })
+}
+
+fn counts<W>(monkeys: &[Monkey<W>]) -> Vec<usize> {
+    monkeys.iter().map(|x| x.inspections).collect()
+}
+
+fn business_overflowed() -> AocError {
+    AocError::puzzle(DAY, "monkey business overflowed")
+}
+
+/// The product of the `top` highest inspection counts. Any two counts
+/// multiply without overflowing, but more can fail.
+///
+/// ```
+/// # use advent_of_code_2022::day11::{inspections, monkey_business, parse_monkeys, Backend, Rules};
+/// # use std::fs;
+/// assert_eq!(monkey_business(&[101, 95, 7, 105], 2).unwrap(), 10605);
+/// assert_eq!(monkey_business(&[101, 95, 7, 105], 3).unwrap(), 1007475);
+/// let max = usize::MAX;
+/// assert_eq!(monkey_business(&[max, 1, max], 2).unwrap(), max as u128 * max as u128);
+/// assert_eq!(
+///     monkey_business(&[max, max, max], 3).unwrap_err().to_string(),
+///     "day 11: monkey business overflowed"
+/// );
+///
+/// let monkeys = parse_monkeys(&fs::read_to_string("day11a.txt").unwrap()).unwrap();
+/// let rules = Rules { rounds: 1_000_000_000, backend: Backend::Residues, ..Rules::PART2 };
+/// let inspections = inspections(&monkeys, &rules, &[], |_| {}).unwrap();
+/// assert_eq!(monkey_business(&inspections, 2).unwrap(), 27142382184098982504);
+/// ```
+pub fn monkey_business(
+    inspections: &[usize],
+    top: usize,
+) -> Result<u128, AocError> {
+    inspections
         .iter()
-        .map(|monkey| monkey.inspections)
         .sorted_by(|a, b| b.cmp(a))
-        .take(2)
-        .product())
+        .take(top)
+        .try_fold(1_u128, |product, &x| product.checked_mul(x as u128))
+        .ok_or_else(business_overflowed)
+}
+
+/// How to play: for how long, how much relief there is after each
+/// inspection, and how worry levels are kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rules {
+    pub rounds: usize,
+    pub relief: i64,
+    pub backend: Backend,
+}
+
+impl Rules {
+    pub const PART1: Rules = Rules {
+        rounds: 20,
+        relief: 3,
+        backend: Backend::Checked,
+    };
+    pub const PART2: Rules = Rules {
+        rounds: 10_000,
+        relief: 1,
+        backend: Backend::Modulo,
+    };
+}
+
+/// How many items each monkey inspects when a copy of `monkeys` is played
+/// by `rules`, calling `observe` as [`play_observed`] does. Items played as
+/// residues aren't held by the monkeys between rounds, so they can't be
+/// observed.
+///
+/// ```
+/// # use advent_of_code_2022::day11::{inspections, parse_monkeys, Backend, Rules};
+/// # use std::fs;
+/// let monkeys = parse_monkeys(&fs::read_to_string("day11a.txt").unwrap()).unwrap();
+/// for backend in [Backend::Checked, Backend::Big] {
+///     let rules = Rules { backend, ..Rules::PART1 };
+///     assert_eq!(inspections(&monkeys, &rules, &[], |_| {}).unwrap(), vec![101, 95, 7, 105]);
+/// }
+/// let rules = Rules { backend: Backend::Modulo, ..Rules::PART1 };
+/// assert_eq!(
+///     inspections(&monkeys, &rules, &[], |_| {}).unwrap_err().to_string(),
+///     "day 11: levels kept modulo the divisors can't be divided, so relief \
+///      must be 1"
+/// );
+/// let rules = Rules { rounds: 1000, ..Rules::PART2 };
+/// assert_eq!(inspections(&monkeys, &rules, &[], |_| {}).unwrap(), vec![5204, 4792, 199, 5192]);
+/// let rules = Rules { backend: Backend::Checked, ..rules };
+/// assert_eq!(
+///     inspections(&monkeys, &rules, &[], |_| {}).unwrap_err().to_string(),
+///     "day 11: worry level overflowed when monkey 2 inspected an item at 3740147530"
+/// );
+///
+/// let notes = [(1_000_000_007, 1, 2), (1_000_000_009, 2, 0), (998_244_353, 0, 1)]
+///     .iter()
+///     .enumerate()
+///     .map(|(i, (divisor, yes, no))| format!(
+///         "Monkey {i}:\n  Starting items: 79\n  Operation: new = old + 3\n  \
+///          Test: divisible by {divisor}\n    If true: throw to monkey {yes}\n    \
+///          If false: throw to monkey {no}"
+///     ))
+///     .collect::<Vec<_>>()
+///     .join("\n\n");
+/// let monkeys = parse_monkeys(&notes).unwrap();
+/// assert_eq!(
+///     inspections(&monkeys, &Rules::PART2, &[], |_| {}).unwrap_err().to_string(),
+///     "day 11: the divisors have no common multiple that fits in 64 bits, so \
+///      keep worry levels as big or residues instead"
+/// );
+/// let rules = Rules { backend: Backend::Residues, ..Rules::PART2 };
+/// assert_eq!(inspections(&monkeys, &rules, &[], |_| {}).unwrap(), vec![14999, 15000, 15000]);
+/// ```
+pub fn inspections(
+    monkeys: &[Monkey],
+    rules: &Rules,
+    after: &[usize],
+    observe: impl FnMut(Report),
+) -> Result<Vec<usize>, AocError> {
+    let Rules {
+        rounds,
+        relief,
+        backend,
+    } = *rules;
+    match backend {
+        Backend::Modulo if relief != 1 => Err(AocError::puzzle(
+            DAY,
+            "levels kept modulo the divisors can't be divided, so relief must \
+             be 1",
+        )),
+        Backend::Modulo => {
+            let modulo = common_multiple(monkeys)?;
+            let mut monkeys = monkeys.to_vec();
+            play_observed(
+                &mut monkeys,
+                rounds,
+                relief,
+                Some(modulo),
+                after,
+                observe,
+            )?;
+            Ok(counts(&monkeys))
+        }
+        Backend::Checked => {
+            let mut monkeys = monkeys.to_vec();
+            play_observed(&mut monkeys, rounds, relief, None, after, observe)?;
+            Ok(counts(&monkeys))
+        }
+        Backend::Big => {
+            let mut monkeys: Vec<_> =
+                monkeys.iter().map(Monkey::convert::<BigInt>).collect();
+            play_observed(&mut monkeys, rounds, relief, None, after, observe)?;
+            Ok(counts(&monkeys))
+        }
+        Backend::Residues if relief != 1 => Err(AocError::puzzle(
+            DAY,
+            "residues can't be divided, so relief must be 1",
+        )),
+        Backend::Residues if !after.is_empty() => Err(AocError::puzzle(
+            DAY,
+            "rounds can't be reported when playing with residues",
+        )),
+        Backend::Residues => residue_inspections(monkeys, rounds, true),
+    }
+}
+
+pub fn solve_part1(input: &str) -> Result<u128, AocError> {
+    let inspections =
+        inspections(&parse_monkeys(input)?, &Rules::PART1, &[], |_| {})?;
+    monkey_business(&inspections, 2)
+}
+
+pub fn solve_part2(input: &str) -> Result<u128, AocError> {
+    let inspections =
+        inspections(&parse_monkeys(input)?, &Rules::PART2, &[], |_| {})?;
+    monkey_business(&inspections, 2)
+}
+
+/// ```
+/// # use advent_of_code_2022::day11::puzzle1;
+/// assert_eq!(puzzle1("day11a.txt").unwrap(), 10605);
+pub fn puzzle1(path: &str) -> Result<u128, AocError> {
+    solve_part1(&fs::read_to_string(path)?)
+}
+
+pub fn puzzle2(path: &str) -> Result<u128, AocError> {
+    solve_part2(&fs::read_to_string(path)?)
+}
+
+pub struct Day11 {
+    monkeys: Vec<Monkey>,
+}
+
+impl Solution for Day11 {
+    fn parse(input: &str) -> Result<Self, AocError> {
+        Ok(Day11 {
+            monkeys: parse_monkeys(input)?,
+        })
+    }
+
+    fn part1(&self) -> Result<Answer, AocError> {
+        let inspections =
+            inspections(&self.monkeys, &Rules::PART1, &[], |_| {})?;
+        Answer::try_from(monkey_business(&inspections, 2)?)
+            .map_err(|_| business_overflowed())
+    }
+
+    fn part2(&self) -> Result<Answer, AocError> {
+        let inspections =
+            inspections(&self.monkeys, &Rules::PART2, &[], |_| {})?;
+        Answer::try_from(monkey_business(&inspections, 2)?)
+            .map_err(|_| business_overflowed())
+    }
 }
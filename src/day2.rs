@@ -1,6 +1,8 @@
-use std::error::Error;
+use crate::{input, Answer, AocError, Solution};
 use std::fs;
 
+const DAY: u8 = 2;
+
 #[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
 pub enum RPS {
     Rock,
@@ -45,61 +47,93 @@ impl RPS {
     }
 }
 
-pub fn parse_line(line: &str) -> Result<(RPS, RPS), &'static str> {
-    let mut iterator = line
-        .chars()
-        .filter(|x| "ABCXYZ".contains(*x))
-        .map(|x| RPS::from(x).unwrap());
-    Ok((
-        match iterator.next() {
-            Some(x) => x,
-            None => return Err("Not enough arguments in line!"),
-        },
-        match iterator.next() {
-            Some(x) => x,
-            None => return Err("Not enough arguments in line!"),
-        },
-    ))
+pub fn parse_line(line: &str) -> Result<(RPS, RPS), AocError> {
+    let mut args = line.split_whitespace();
+    let mut shape = |letters: &str| {
+        let arg = args.next().unwrap_or_default();
+        let mut chars = arg.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) if letters.contains(c) => Ok(RPS::from(c).unwrap()),
+            _ => Err(AocError::parse(
+                DAY,
+                line,
+                arg,
+                format!("expected one of {letters}"),
+            )),
+        }
+    };
+    let round = (shape("ABC")?, shape("XYZ")?);
+    if let Some(arg) = args.next() {
+        return Err(AocError::parse(DAY, line, arg, "unexpected argument"));
+    }
+    Ok(round)
 }
 
-pub fn score_line(line: &str) -> Result<i32, &'static str> {
-    let mut iterator = line.chars().filter(|x| "ABCXYZ".contains(*x));
-    let (opponent, player) = (iterator.next(), iterator.next());
-    let (Some(opponent), Some(player)) = (opponent, player) else {
-        return Err("Not enough arguments in line.");
-    };
-    Ok(match (opponent, player) {
-        ('A', 'X') => 3,
-        ('B', 'X') => 1,
-        ('C', 'X') => 2,
-        ('A', 'Y') => 4,
-        ('B', 'Y') => 5,
-        ('C', 'Y') => 6,
-        ('A', 'Z') => 8,
-        ('B', 'Z') => 9,
-        ('C', 'Z') => 7,
-        _ => return Err("Not a valid turn."),
-    })
+pub fn score_line(line: &str) -> Result<i32, AocError> {
+    Ok(score_outcome(&parse_line(line)?))
+}
+
+/// Scores a round where the second column is the desired outcome rather than
+/// the player's shape: rock to lose, paper to draw and scissors to win.
+pub fn score_outcome(line: &(RPS, RPS)) -> i32 {
+    match line {
+        (RPS::Rock, RPS::Rock) => 3,
+        (RPS::Paper, RPS::Rock) => 1,
+        (RPS::Scissors, RPS::Rock) => 2,
+        (RPS::Rock, RPS::Paper) => 4,
+        (RPS::Paper, RPS::Paper) => 5,
+        (RPS::Scissors, RPS::Paper) => 6,
+        (RPS::Rock, RPS::Scissors) => 8,
+        (RPS::Paper, RPS::Scissors) => 9,
+        (RPS::Scissors, RPS::Scissors) => 7,
+    }
 }
 
 pub fn score(line: &(RPS, RPS)) -> i32 {
     line.1.score(&line.0)
 }
 
-pub fn puzzle1(path: &str) -> Result<i32, Box<dyn Error>> {
-    let mut sum = 0;
-    for line in fs::read_to_string(path)?.lines() {
-        sum += score(&parse_line(line)?);
-    }
-    Ok(sum)
+/// ```
+/// # use advent_of_code_2022::day2::{solve_part1, solve_part2};
+/// assert_eq!(solve_part1("A Y\nB X\nC Z").unwrap(), 15);
+/// assert_eq!(solve_part2("A Y\nB X\nC Z").unwrap(), 12);
+pub fn solve_part1(input: &str) -> Result<i32, AocError> {
+    Ok(input::parse_lines(input, parse_line)?
+        .iter()
+        .map(score)
+        .sum())
 }
 
-pub fn puzzle2(path: &str) -> Result<i32, Box<dyn Error>> {
-    let mut sum = 0;
-    for line in fs::read_to_string(path)?.lines() {
-        sum += score_line(line)?;
+pub fn solve_part2(input: &str) -> Result<i32, AocError> {
+    Ok(input::parse_lines(input, score_line)?.iter().sum())
+}
+
+pub fn puzzle1(path: &str) -> Result<i32, AocError> {
+    solve_part1(&fs::read_to_string(path)?)
+}
+
+pub fn puzzle2(path: &str) -> Result<i32, AocError> {
+    solve_part2(&fs::read_to_string(path)?)
+}
+
+pub struct Day2 {
+    rounds: Vec<(RPS, RPS)>,
+}
+
+impl Solution for Day2 {
+    fn parse(input: &str) -> Result<Self, AocError> {
+        Ok(Day2 {
+            rounds: input::parse_lines(input, parse_line)?,
+        })
+    }
+
+    fn part1(&self) -> Result<Answer, AocError> {
+        Ok(self.rounds.iter().map(score).sum::<i32>().into())
+    }
+
+    fn part2(&self) -> Result<Answer, AocError> {
+        Ok(self.rounds.iter().map(score_outcome).sum::<i32>().into())
     }
-    Ok(sum)
 }
 
 #[cfg(test)]
@@ -127,8 +161,12 @@ pub mod tests {
     #[test]
     fn cannot_parse_invalid_line() {
         assert_eq!(
-            Err("Not enough arguments in line!"),
-            day2::parse_line("FGA432HL KNM43")
+            day2::parse_line("FGA432HL KNM43").unwrap_err().to_string(),
+            "day 2, line 1, column 1: expected one of ABC (\"FGA432HL\")"
+        );
+        assert_eq!(
+            day2::solve_part1("A Y\nB").unwrap_err().to_string(),
+            "day 2, line 2, column 2: expected one of XYZ (\"B\")"
         );
     }
 }
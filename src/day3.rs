@@ -1,7 +1,9 @@
+use crate::{input, Answer, AocError, Solution};
 use std::collections::HashSet;
-use std::error::Error;
 use std::fs;
 
+const DAY: u8 = 3;
+
 pub fn priority(letter: char) -> Option<i32> {
     if letter.is_ascii_alphabetic() {
         Some(
@@ -37,36 +39,91 @@ pub fn find_shared(x: &str, y: &str, z: &str) -> Option<char> {
     z.chars().find(|c| x_y_letters.contains(c))
 }
 
-pub fn puzzle1(path: &str) -> Result<i32, Box<dyn Error>> {
-    Ok({
-        let mut acc = 0;
-        for line in fs::read_to_string(path)?.lines() {
-            acc += match find_duplicate(line).map(priority) {
-                Some(Some(x)) => Ok(x),
-                Some(None) => Err(format!("{line}: Not a letter!")),
-                None => Err(format!("{line}: No duplicates!")),
-            }?;
-        }
-        acc
-    })
+/// Checks that a rucksack only holds items, which are ASCII letters.
+pub fn parse_rucksack(line: &str) -> Result<&str, AocError> {
+    match line.char_indices().find(|(_, c)| priority(*c).is_none()) {
+        Some((i, c)) => Err(AocError::parse(
+            DAY,
+            line,
+            &line[i..i + c.len_utf8()],
+            "not a letter",
+        )),
+        None => Ok(line),
+    }
+}
+
+pub fn sum_duplicates<'a>(
+    lines: impl IntoIterator<Item = &'a str>,
+) -> Result<i32, AocError> {
+    let mut acc = 0;
+    for (i, line) in lines.into_iter().enumerate() {
+        let line = parse_rucksack(line).map_err(|err| err.offset_lines(i))?;
+        acc += find_duplicate(line).and_then(priority).ok_or_else(|| {
+            AocError::parse(DAY, line, line, "no item in both compartments")
+                .offset_lines(i)
+        })?;
+    }
+    Ok(acc)
+}
+
+pub fn sum_badges<'a>(
+    lines: impl IntoIterator<Item = &'a str>,
+) -> Result<i32, AocError> {
+    let mut acc = 0;
+    let mut lines = lines.into_iter().enumerate().map(|(i, line)| {
+        parse_rucksack(line).map_err(|err| err.offset_lines(i))
+    });
+    let mut i = 0;
+    while let (Some(x), Some(y), Some(z)) =
+        (lines.next(), lines.next(), lines.next())
+    {
+        let (x, y, z) = (x?, y?, z?);
+        acc += find_shared(x, y, z).and_then(priority).ok_or_else(|| {
+            AocError::parse(DAY, x, x, "no item shared by this group of three")
+                .offset_lines(i)
+        })?;
+        i += 3;
+    }
+    Ok(acc)
+}
+
+pub fn solve_part1(input: &str) -> Result<i32, AocError> {
+    sum_duplicates(input.lines())
 }
 
-pub fn puzzle2(path: &str) -> Result<i32, Box<dyn Error>> {
-    Ok({
-        let mut acc = 0;
-        let text = fs::read_to_string(path)?;
-        let mut lines = text.lines();
-        while let (Some(x), Some(y), Some(z)) =
-            (lines.next(), lines.next(), lines.next())
-        {
-            acc += match find_shared(x, y, z).map(priority) {
-                Some(Some(x)) => Ok(x),
-                Some(None) => Err(format!("{x}, {y}, {z}: Not a letter!")),
-                None => Err(format!("{x}, {y}, {z}: No shared letters!")),
-            }?;
-        }
-        acc
-    })
+pub fn solve_part2(input: &str) -> Result<i32, AocError> {
+    sum_badges(input.lines())
+}
+
+pub fn puzzle1(path: &str) -> Result<i32, AocError> {
+    solve_part1(&fs::read_to_string(path)?)
+}
+
+pub fn puzzle2(path: &str) -> Result<i32, AocError> {
+    solve_part2(&fs::read_to_string(path)?)
+}
+
+pub struct Day3 {
+    rucksacks: Vec<String>,
+}
+
+impl Solution for Day3 {
+    fn parse(input: &str) -> Result<Self, AocError> {
+        Ok(Day3 {
+            rucksacks: input::parse_lines(input, parse_rucksack)?
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+        })
+    }
+
+    fn part1(&self) -> Result<Answer, AocError> {
+        Ok(sum_duplicates(self.rucksacks.iter().map(String::as_str))?.into())
+    }
+
+    fn part2(&self) -> Result<Answer, AocError> {
+        Ok(sum_badges(self.rucksacks.iter().map(String::as_str))?.into())
+    }
 }
 
 #[cfg(test)]
@@ -80,4 +137,16 @@ pub mod tests {
         assert_eq!(Some(27), day3::priority('A'));
         assert_eq!(Some(52), day3::priority('Z'));
     }
+
+    #[test]
+    fn cannot_sum_without_duplicates() {
+        assert_eq!(
+            day3::solve_part1("abca\nabcd").unwrap_err().to_string(),
+            "day 3, line 2, column 1: no item in both compartments (\"abcd\")"
+        );
+        assert_eq!(
+            day3::solve_part2("ab\nbc\nb4").unwrap_err().to_string(),
+            "day 3, line 3, column 2: not a letter (\"4\")"
+        );
+    }
 }
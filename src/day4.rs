@@ -1,8 +1,10 @@
+use crate::{input, Answer, AocError, Solution};
 use std::cmp::Ordering::{Equal, Greater, Less};
-use std::error::Error;
 use std::fs;
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+const DAY: u8 = 4;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub struct Range {
     pub start: i32,
     pub end: i32,
@@ -50,44 +52,83 @@ pub fn mutual_contains(x: &Range, y: &Range) -> bool {
     x.contains(y) || y.contains(x)
 }
 
-pub fn parse_line(line: &str) -> Option<(Range, Range)> {
-    let mut halves = line.split(',');
-    let (mut left, mut right) =
-        (halves.next()?.split('-'), halves.next()?.split('-'));
-    Some((
-        Range::from(
-            left.next()?.parse().unwrap(),
-            left.next()?.parse().unwrap(),
-        )?,
-        Range::from(
-            right.next()?.parse().unwrap(),
-            right.next()?.parse().unwrap(),
-        )?,
-    ))
+/// ```
+/// # use advent_of_code_2022::day4::{parse_line, Range};
+/// assert_eq!(
+///     parse_line("2-4,6-8").unwrap(),
+///     (Range::from(2, 4).unwrap(), Range::from(6, 8).unwrap())
+/// );
+/// assert_eq!(
+///     parse_line("2-4,8-6").unwrap_err().to_string(),
+///     "day 4, line 1, column 5: range ends before it starts (\"8-6\")"
+/// );
+pub fn parse_line(line: &str) -> Result<(Range, Range), AocError> {
+    let Some((left, right)) = line.split_once(',') else {
+        return Err(AocError::parse(DAY, line, "", "expected two ranges"));
+    };
+    Ok((parse_range(line, left)?, parse_range(line, right)?))
 }
 
-pub fn puzzle1(path: &str) -> Result<i32, Box<dyn Error>> {
-    let mut acc = 0;
-    for line in fs::read_to_string(path)?.lines() {
-        let Some((left, right)) = parse_line(line) else {
-            return Err(format!("Could not parse line: {line}").into());
-        };
-        if mutual_contains(&left, &right) {
-            acc += 1;
-        }
-    }
-    Ok(acc)
+fn parse_range(line: &str, range: &str) -> Result<Range, AocError> {
+    let err = |token, message: &str| AocError::parse(DAY, line, token, message);
+    let Some((start, end)) = range.split_once('-') else {
+        return Err(err(range, "expected a range like 2-4"));
+    };
+    let start = start.parse().map_err(|_| err(start, "expected a number"))?;
+    let end = end.parse().map_err(|_| err(end, "expected a number"))?;
+    Range::from(start, end)
+        .ok_or_else(|| err(range, "range ends before it starts"))
 }
 
-pub fn puzzle2(path: &str) -> Result<i32, Box<dyn Error>> {
-    let mut acc = 0;
-    for line in fs::read_to_string(path)?.lines() {
-        let Some((left, right)) = parse_line(line) else {
-            return Err(format!("Could not parse line: {line}").into());
-        };
-        if left.overlaps(&right) {
-            acc += 1;
-        }
+pub fn parse_pairs(text: &str) -> Result<Vec<(Range, Range)>, AocError> {
+    input::parse_lines(text, parse_line)
+}
+
+pub fn count_contained(pairs: &[(Range, Range)]) -> i32 {
+    pairs.iter().filter(|(l, r)| mutual_contains(l, r)).count() as i32
+}
+
+pub fn count_overlapping(pairs: &[(Range, Range)]) -> i32 {
+    pairs.iter().filter(|(l, r)| l.overlaps(r)).count() as i32
+}
+
+/// ```
+/// # use advent_of_code_2022::day4::{solve_part1, solve_part2};
+/// let input = "2-8,3-7\n2-4,6-8\n5-7,7-9";
+/// assert_eq!(solve_part1(input).unwrap(), 1);
+/// assert_eq!(solve_part2(input).unwrap(), 2);
+pub fn solve_part1(input: &str) -> Result<i32, AocError> {
+    Ok(count_contained(&parse_pairs(input)?))
+}
+
+pub fn solve_part2(input: &str) -> Result<i32, AocError> {
+    Ok(count_overlapping(&parse_pairs(input)?))
+}
+
+pub fn puzzle1(path: &str) -> Result<i32, AocError> {
+    solve_part1(&fs::read_to_string(path)?)
+}
+
+pub fn puzzle2(path: &str) -> Result<i32, AocError> {
+    solve_part2(&fs::read_to_string(path)?)
+}
+
+pub struct Day4 {
+    pairs: Vec<(Range, Range)>,
+}
+
+impl Solution for Day4 {
+    fn parse(input: &str) -> Result<Self, AocError> {
+        Ok(Day4 {
+            pairs: parse_pairs(input)?,
+        })
+    }
+
+    fn part1(&self) -> Result<Answer, AocError> {
+        Ok(count_contained(&self.pairs).into())
+    }
+
+    fn part2(&self) -> Result<Answer, AocError> {
+        Ok(count_overlapping(&self.pairs).into())
     }
-    Ok(acc)
 }
@@ -1,115 +1,264 @@
+use crate::{input, Answer, AocError, Solution};
 use std::collections::VecDeque;
-use std::error::Error;
 use std::fs;
 
+const DAY: u8 = 5;
+
+/// The amount of crates to move, and the stacks to move them from and to.
+pub type Move = (i32, usize, usize);
+
 /// ```
 /// # use advent_of_code_2022::day5::parse_cargo;
 /// assert_eq!(parse_cargo("\
 /// [A]     [D]
 /// [B] [C] [E]
-///  1   2   3"),
+///  1   2   3").unwrap(),
 /// vec![vec!['B', 'A'], vec!['C'], vec!['E', 'D']]
 /// );
 /// ```
-pub fn parse_cargo(cargo: &str) -> Vec<Vec<char>> {
+pub fn parse_cargo(cargo: &str) -> Result<Vec<Vec<char>>, AocError> {
     let mut stacks = Vec::new();
-    let mut lines = cargo.lines().rev();
-    if let Some(x) = lines.next() {
+    let lines: Vec<_> = cargo.lines().collect();
+    let mut lines = lines.into_iter().enumerate().rev();
+    if let Some((_, x)) = lines.next() {
         x.split_whitespace().for_each(|_| stacks.push(Vec::new()));
     }
-    lines.for_each(|x| {
-        x.char_indices()
-            .filter(|(i, c)| i % 4 == 1 && *c != ' ')
-            .for_each(|(i, c)| {
-                stacks[i / 4].push(c);
-            });
-    });
-    stacks
+    for (n, x) in lines {
+        for (i, c) in x.char_indices().filter(|(i, c)| i % 4 == 1 && *c != ' ')
+        {
+            let Some(stack) = stacks.get_mut(i / 4) else {
+                return Err(AocError::parse(
+                    DAY,
+                    x,
+                    &x[i..i + c.len_utf8()],
+                    "crate is not above a numbered stack",
+                )
+                .offset_lines(n));
+            };
+            stack.push(c);
+        }
+    }
+    Ok(stacks)
 }
 
 /// ```
 /// # use advent_of_code_2022::day5::parse_instruction;
 /// assert_eq!(parse_instruction("move 1 from 6 to 2").unwrap(), (1, 6, 2));
+/// assert_eq!(
+///     parse_instruction("move 1 from 0 to 2").unwrap_err().to_string(),
+///     "day 5, line 1, column 13: stacks are numbered from 1 (\"0\")"
+/// );
 /// ```
-pub fn parse_instruction(
-    instruction: &str,
-) -> Result<(i32, usize, usize), Box<dyn Error>> {
-    let mut iter = instruction
-        .split_whitespace()
-        .filter(|&s| s.chars().next().unwrap_or(' ').is_ascii_digit());
-    let err = "Could not parse instruction";
-    Ok((
-        iter.next().ok_or(err)?.parse()?,
-        iter.next().ok_or(err)?.parse()?,
-        iter.next().ok_or(err)?.parse()?,
-    ))
+pub fn parse_instruction(instruction: &str) -> Result<Move, AocError> {
+    let mut args = instruction.split_whitespace();
+    expect_word(instruction, args.next(), "move")?;
+    let amount = parse_number(instruction, args.next())?;
+    expect_word(instruction, args.next(), "from")?;
+    let from = parse_stack(instruction, args.next())?;
+    expect_word(instruction, args.next(), "to")?;
+    let to = parse_stack(instruction, args.next())?;
+    if let Some(extra) = args.next() {
+        return Err(AocError::parse(
+            DAY,
+            instruction,
+            extra,
+            "unexpected argument",
+        ));
+    }
+    Ok((amount, from, to))
+}
+
+fn expect_word(
+    line: &str,
+    arg: Option<&str>,
+    expected: &str,
+) -> Result<(), AocError> {
+    match arg {
+        Some(x) if x == expected => Ok(()),
+        x => Err(AocError::parse(
+            DAY,
+            line,
+            x.unwrap_or_default(),
+            format!("expected {expected}"),
+        )),
+    }
+}
+
+fn parse_number(line: &str, arg: Option<&str>) -> Result<i32, AocError> {
+    let arg = arg.unwrap_or_default();
+    arg.parse()
+        .map_err(|_| AocError::parse(DAY, line, arg, "expected a number"))
+}
+
+fn parse_stack(line: &str, arg: Option<&str>) -> Result<usize, AocError> {
+    match usize::try_from(parse_number(line, arg)?) {
+        Ok(x) if x >= 1 => Ok(x),
+        _ => Err(AocError::parse(
+            DAY,
+            line,
+            arg.unwrap_or_default(),
+            "stacks are numbered from 1",
+        )),
+    }
 }
 
 pub fn perform_instruction(
     instruction: &str,
     cargo: &mut [Vec<char>],
-) -> Result<(), Box<dyn Error>> {
-    let (amount, from, to) = parse_instruction(instruction)?;
-    let err = "tried to remove from empty stack";
+) -> Result<(), AocError> {
+    apply_move(parse_instruction(instruction)?, cargo)
+}
+
+pub fn perform_instruction_grouped(
+    instruction: &str,
+    cargo: &mut [Vec<char>],
+) -> Result<(), AocError> {
+    apply_move_grouped(parse_instruction(instruction)?, cargo)
+}
+
+fn take(cargo: &mut [Vec<char>], stack: usize) -> Result<char, AocError> {
+    cargo
+        .get_mut(stack.wrapping_sub(1))
+        .ok_or_else(|| AocError::puzzle(DAY, format!("no stack {stack}")))?
+        .pop()
+        .ok_or_else(|| AocError::puzzle(DAY, format!("stack {stack} is empty")))
+}
+
+fn put(
+    cargo: &mut [Vec<char>],
+    stack: usize,
+    item: char,
+) -> Result<(), AocError> {
+    cargo
+        .get_mut(stack.wrapping_sub(1))
+        .ok_or_else(|| AocError::puzzle(DAY, format!("no stack {stack}")))?
+        .push(item);
+    Ok(())
+}
+
+pub fn apply_move(
+    (amount, from, to): Move,
+    cargo: &mut [Vec<char>],
+) -> Result<(), AocError> {
     for _ in 0..amount {
-        let top = cargo[from - 1].pop().ok_or(err)?;
-        cargo[to - 1].push(top);
+        let top = take(cargo, from)?;
+        put(cargo, to, top)?;
     }
     Ok(())
 }
 
-pub fn perform_instruction_grouped(
-    instruction: &str,
+pub fn apply_move_grouped(
+    (amount, from, to): Move,
     cargo: &mut [Vec<char>],
-) -> Result<(), Box<dyn Error>> {
-    let (amount, from, to) = parse_instruction(instruction)?;
-    let err = "tried to remove from empty stack";
+) -> Result<(), AocError> {
     let mut temp = VecDeque::new();
     for _ in 0..amount {
-        temp.push_front(cargo[from - 1].pop().ok_or(err)?);
+        temp.push_front(take(cargo, from)?);
     }
     for item in temp {
-        cargo[to - 1].push(item);
+        put(cargo, to, item)?;
     }
     Ok(())
 }
 
-pub fn get_tops(cargo: &mut [Vec<char>]) -> Result<String, Box<dyn Error>> {
+pub fn get_tops(cargo: &mut [Vec<char>]) -> Result<String, AocError> {
     let mut tops = String::new();
-    for stack in cargo {
-        tops.push(*stack.last().ok_or("empty stack")?);
+    for (i, stack) in cargo.iter().enumerate() {
+        tops.push(*stack.last().ok_or_else(|| {
+            AocError::puzzle(DAY, format!("stack {} is empty", i + 1))
+        })?);
     }
     Ok(tops)
 }
 
+/// Splits the input into the cargo drawing and the instructions.
+pub fn split_input(text: &str) -> Result<(&str, &str), AocError> {
+    match input::sections(text)[..] {
+        [cargo, instructions] => Ok((cargo, instructions)),
+        _ => Err(AocError::puzzle(
+            DAY,
+            "need to separate cargo and instructions with a blank line",
+        )),
+    }
+}
+
+/// Parses the cargo and its moves, checking that every move is between
+/// existing stacks.
+pub fn parse_input(
+    text: &str,
+) -> Result<(Vec<Vec<char>>, Vec<Move>), AocError> {
+    let (cargo, instructions) = split_input(text)?;
+    let stacks = parse_cargo(cargo)?;
+    let moves = input::parse_lines(instructions, |line| {
+        let (amount, from, to) = parse_instruction(line)?;
+        if from.max(to) > stacks.len() {
+            return Err(AocError::parse(
+                DAY,
+                line,
+                "",
+                format!("there are only {} stacks", stacks.len()),
+            ));
+        }
+        Ok((amount, from, to))
+    })
+    .map_err(|err| err.offset_lines(input::line_of(text, instructions)))?;
+    Ok((stacks, moves))
+}
+
+pub fn solve_part1(input: &str) -> Result<String, AocError> {
+    let (mut cargo, moves) = parse_input(input)?;
+    for step in moves {
+        apply_move(step, &mut cargo)?;
+    }
+    get_tops(&mut cargo)
+}
+
+pub fn solve_part2(input: &str) -> Result<String, AocError> {
+    let (mut cargo, moves) = parse_input(input)?;
+    for step in moves {
+        apply_move_grouped(step, &mut cargo)?;
+    }
+    get_tops(&mut cargo)
+}
+
 /// ```
 /// # use advent_of_code_2022::day5::puzzle1;
 /// assert_eq!(puzzle1("day5.txt").unwrap(), "NTWZZWHFV");
-pub fn puzzle1(path: &str) -> Result<String, Box<dyn Error>> {
-    let text = fs::read_to_string(path)?;
-    let err = "need to separate cargo and instructions with a new line";
-    let separator = text.find("\n\r\n").ok_or(err)?;
-    let cargo = &text[..separator];
-    let instructions = &text[separator + 3..];
-    let mut cargo = parse_cargo(cargo);
-    for line in instructions.lines() {
-        perform_instruction(line, &mut cargo)?;
-    }
-    get_tops(&mut cargo)
+pub fn puzzle1(path: &str) -> Result<String, AocError> {
+    solve_part1(&fs::read_to_string(path)?)
 }
 
 /// ```
 /// # use advent_of_code_2022::day5::puzzle2;
 /// assert_eq!(puzzle2("day5.txt").unwrap(), "BRZGFVBTJ");
-pub fn puzzle2(path: &str) -> Result<String, Box<dyn Error>> {
-    let text = fs::read_to_string(path)?;
-    let err = "need to separate cargo and instructions with a new line";
-    let separator = text.find("\n\r\n").ok_or(err)?;
-    let cargo = &text[..separator];
-    let instructions = &text[separator + 3..];
-    let mut cargo = parse_cargo(cargo);
-    for line in instructions.lines() {
-        perform_instruction_grouped(line, &mut cargo)?;
+pub fn puzzle2(path: &str) -> Result<String, AocError> {
+    solve_part2(&fs::read_to_string(path)?)
+}
+
+pub struct Day5 {
+    cargo: Vec<Vec<char>>,
+    moves: Vec<Move>,
+}
+
+impl Solution for Day5 {
+    fn parse(input: &str) -> Result<Self, AocError> {
+        let (cargo, moves) = parse_input(input)?;
+        Ok(Day5 { cargo, moves })
+    }
+
+    fn part1(&self) -> Result<Answer, AocError> {
+        let mut cargo = self.cargo.clone();
+        for &step in &self.moves {
+            apply_move(step, &mut cargo)?;
+        }
+        Ok(get_tops(&mut cargo)?.into())
+    }
+
+    fn part2(&self) -> Result<Answer, AocError> {
+        let mut cargo = self.cargo.clone();
+        for &step in &self.moves {
+            apply_move_grouped(step, &mut cargo)?;
+        }
+        Ok(get_tops(&mut cargo)?.into())
     }
-    get_tops(&mut cargo)
 }
@@ -1,32 +1,71 @@
+use crate::{Answer, AocError, Solution};
 use itertools::Itertools;
-use std::{collections::VecDeque, error::Error, fs};
+use std::{collections::VecDeque, fs};
 
-pub fn puzzle1(path: &str) -> Result<Option<usize>, Box<dyn Error>> {
-    let text = fs::read_to_string(path)?;
+const DAY: u8 = 6;
+
+/// Finds the number of characters read once the last `len` were all unique.
+///
+/// ```
+/// # use advent_of_code_2022::day6::find_marker;
+/// assert_eq!(find_marker("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 4), Some(7));
+/// assert_eq!(find_marker("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 14), Some(19));
+/// assert_eq!(find_marker("aaaaa", 4), None);
+pub fn find_marker(text: &str, len: usize) -> Option<usize> {
     let mut recent = VecDeque::new();
     for (i, c) in text.char_indices() {
         recent.push_front(c);
-        if recent.len() > 4 {
+        if recent.len() > len {
             recent.pop_back();
             if recent.iter().all_unique() {
-                return Ok(Some(i + 1));
+                return Some(i + 1);
             }
         }
     }
-    Ok(None)
+    None
 }
 
-pub fn puzzle2(path: &str) -> Result<Option<usize>, Box<dyn Error>> {
-    let text = fs::read_to_string(path)?;
-    let mut recent = VecDeque::new();
-    for (i, c) in text.char_indices() {
-        recent.push_front(c);
-        if recent.len() > 14 {
-            recent.pop_back();
-            if recent.iter().all_unique() {
-                return Ok(Some(i + 1));
-            }
-        }
+/// ```
+/// # use advent_of_code_2022::day6::solve_part1;
+/// assert_eq!(solve_part1("bvwbjplbgvbhsrlpgdmjqwftvncz").unwrap(), 5);
+/// assert_eq!(
+///     solve_part1("aaaaa").unwrap_err().to_string(),
+///     "day 6: no start-of-packet marker"
+/// );
+pub fn solve_part1(input: &str) -> Result<usize, AocError> {
+    find_marker(input, 4)
+        .ok_or_else(|| AocError::puzzle(DAY, "no start-of-packet marker"))
+}
+
+pub fn solve_part2(input: &str) -> Result<usize, AocError> {
+    find_marker(input, 14)
+        .ok_or_else(|| AocError::puzzle(DAY, "no start-of-message marker"))
+}
+
+pub fn puzzle1(path: &str) -> Result<usize, AocError> {
+    solve_part1(&fs::read_to_string(path)?)
+}
+
+pub fn puzzle2(path: &str) -> Result<usize, AocError> {
+    solve_part2(&fs::read_to_string(path)?)
+}
+
+pub struct Day6 {
+    signal: String,
+}
+
+impl Solution for Day6 {
+    fn parse(input: &str) -> Result<Self, AocError> {
+        Ok(Day6 {
+            signal: input.to_string(),
+        })
+    }
+
+    fn part1(&self) -> Result<Answer, AocError> {
+        Ok(solve_part1(&self.signal)?.into())
+    }
+
+    fn part2(&self) -> Result<Answer, AocError> {
+        Ok(solve_part2(&self.signal)?.into())
     }
-    Ok(None)
 }
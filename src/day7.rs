@@ -1,5 +1,8 @@
+use crate::{input, Answer, AocError, Solution};
 use std::{collections::HashSet, fs};
 
+const DAY: u8 = 7;
+
 pub type FileId = usize;
 
 #[derive(Clone, Debug)]
@@ -68,14 +71,23 @@ impl Finder {
 
     pub fn cd(&mut self, name: &str) -> bool {
         if name == ".." {
-            self.curr = self.curr().unwrap().parent.unwrap();
+            let Some(parent) = self.curr().and_then(|x| x.parent) else {
+                return false;
+            };
+            self.curr = parent;
+            return true;
+        }
+        if name == self.files[0].name {
+            self.curr = 0;
             return true;
         }
         let mut curr = None;
         if let SOC::Children(children) = &self.curr().unwrap().soc {
             for child in children {
                 if let Some(child) = self.files.get(*child) {
-                    if name == child.name {
+                    if name == child.name
+                        && matches!(child.soc, SOC::Children(..))
+                    {
                         curr = Some(child.id);
                         break;
                     }
@@ -97,61 +109,126 @@ impl Finder {
         self.get_mut(self.curr)
     }
 
-    pub fn parse(&mut self, line: &str) {
+    pub fn parse(&mut self, line: &str) -> Result<(), AocError> {
         let mut args = line.split_whitespace();
-        match args.next() {
-            Some("$") => {
-                if args.next() == Some("cd") {
-                    self.cd(args.next().unwrap());
+        let mut arg = |expected: &str| {
+            args.next().ok_or_else(|| {
+                AocError::parse(DAY, line, "", format!("expected {expected}"))
+            })
+        };
+        match arg("a command or listing")? {
+            "$" => match arg("a command")? {
+                "ls" => {}
+                "cd" => {
+                    let name = arg("a directory")?;
+                    if !self.cd(name) {
+                        return Err(AocError::parse(
+                            DAY,
+                            line,
+                            name,
+                            "no such directory",
+                        ));
+                    }
                 }
-            }
-            Some("dir") => self.add(
-                args.next().unwrap().to_string(),
+                x => {
+                    return Err(AocError::parse(
+                        DAY,
+                        line,
+                        x,
+                        "unknown command",
+                    ))
+                }
+            },
+            "dir" => self.add(
+                arg("a directory name")?.to_string(),
                 SOC::Children(HashSet::new()),
             ),
-            Some(x) => self.add(
-                args.next().unwrap().to_string(),
-                SOC::Size(x.parse().unwrap()),
-            ),
-            None => {}
+            x => {
+                let size = x.parse().map_err(|_| {
+                    AocError::parse(DAY, line, x, "expected a file size or dir")
+                })?;
+                self.add(arg("a file name")?.to_string(), SOC::Size(size));
+            }
+        }
+        Ok(())
+    }
+
+    /// Builds the file tree from a terminal session.
+    pub fn from_output(text: &str) -> Result<Finder, AocError> {
+        let mut finder = Finder::from("/");
+        input::parse_lines(text, |line| finder.parse(line))?;
+        Ok(finder)
+    }
+
+    fn dir_sizes(&self) -> impl Iterator<Item = usize> + '_ {
+        self.files
+            .iter()
+            .filter(|f| match f.soc {
+                SOC::Children(..) => true,
+                SOC::Size(..) => false,
+            })
+            .map(|f| f.size(self))
+    }
+
+    pub fn small_dirs_total(&self) -> usize {
+        self.dir_sizes().filter(|&x| x <= 100_000).sum()
+    }
+
+    pub fn dir_to_delete(&self) -> Option<usize> {
+        if self.files[0].size(self) < 40_000_000 {
+            return None;
         }
+        let needed = self.files[0].size(self) - 40_000_000;
+        self.dir_sizes().filter(|&f| f >= needed).min()
     }
 }
 
-pub fn puzzle1(path: &str) -> usize {
-    let mut finder = Finder::from("/");
-    for line in fs::read_to_string(path).unwrap().lines() {
-        finder.parse(line);
-    }
-    finder
-        .files
-        .iter()
-        .filter(|f| match f.soc {
-            SOC::Children(..) => true,
-            SOC::Size(..) => false,
-        })
-        .map(|f| f.size(&finder))
-        .filter(|&x| x <= 100_000)
-        .sum()
+/// ```
+/// # use advent_of_code_2022::day7::solve_part1;
+/// let input = "$ cd /\n$ ls\ndir a\n14848514 b.txt\n$ cd a\n$ ls\n8504 c";
+/// assert_eq!(solve_part1(input).unwrap(), 8504);
+/// assert_eq!(
+///     solve_part1("$ cd /\n$ cd a").unwrap_err().to_string(),
+///     "day 7, line 2, column 6: no such directory (\"a\")"
+/// );
+pub fn solve_part1(input: &str) -> Result<usize, AocError> {
+    Ok(Finder::from_output(input)?.small_dirs_total())
+}
+
+fn enough_space() -> AocError {
+    AocError::puzzle(DAY, "already enough free space")
+}
+
+pub fn solve_part2(input: &str) -> Result<usize, AocError> {
+    Finder::from_output(input)?
+        .dir_to_delete()
+        .ok_or_else(enough_space)
+}
+
+pub fn puzzle1(path: &str) -> Result<usize, AocError> {
+    solve_part1(&fs::read_to_string(path)?)
+}
+
+pub fn puzzle2(path: &str) -> Result<usize, AocError> {
+    solve_part2(&fs::read_to_string(path)?)
+}
+
+pub struct Day7 {
+    finder: Finder,
 }
 
-pub fn puzzle2(path: &str) -> Option<usize> {
-    let mut finder = Finder::from("/");
-    for line in fs::read_to_string(path).unwrap().lines() {
-        finder.parse(line);
-    }
-    if finder.files[0].size(&finder) < 40_000_000 {
-        return None;
-    }
-    let needed = finder.files[0].size(&finder) - 40_000_000;
-    finder
-        .files
-        .iter()
-        .filter(|f| match f.soc {
-            SOC::Children(..) => true,
-            SOC::Size(..) => false,
+impl Solution for Day7 {
+    fn parse(input: &str) -> Result<Self, AocError> {
+        Ok(Day7 {
+            finder: Finder::from_output(input)?,
         })
-        .map(|f| f.size(&finder))
-        .filter(|&f| f >= needed)
-        .min()
+    }
+
+    fn part1(&self) -> Result<Answer, AocError> {
+        Ok(self.finder.small_dirs_total().into())
+    }
+
+    fn part2(&self) -> Result<Answer, AocError> {
+        Ok(self.finder.dir_to_delete().ok_or_else(enough_space)?.into())
+    }
 }
@@ -1,123 +1,117 @@
-use std::{error::Error, fs};
+use crate::grid::{Cell, Grid};
+use crate::point::Direction;
+use crate::{Answer, AocError, Solution};
+use std::fs;
 
-/// ```
-/// # use advent_of_code_2022::day8::vec_2d;
-/// assert_eq!(vec_2d("13\n24"), vec![vec![1, 3], vec![2, 4]]);
-pub fn vec_2d(text: &str) -> Vec<Vec<u8>> {
-    let mut rows = Vec::new();
-    for row in text.lines() {
-        rows.push(
-            row.chars()
-                .filter(char::is_ascii_digit)
-                .map(|c| c.to_digit(10).unwrap() as u8)
-                .collect(),
-        );
-    }
-    rows
-}
+const DAY: u8 = 8;
 
-/// [Source](https://stackoverflow.com/questions/64498617/how-to-transpose-a-vector-of-vectors-in-rust)
+/// Parses a rectangle of tree heights.
+///
 /// ```
-/// # use advent_of_code_2022::day8::transpose;
-/// assert_eq!(transpose(&[vec![1, 2], vec![3, 4]]), vec![vec![1, 3], vec![2, 4]]);
-pub fn transpose<T: Clone>(rect: &[Vec<T>]) -> Vec<Vec<T>> {
-    (0..rect[0].len())
-        .map(|i| rect.iter().map(|inner| inner[i].clone()).collect())
-        .collect()
+/// # use advent_of_code_2022::day8::parse_trees;
+/// assert_eq!(parse_trees("13\n24").unwrap().row(1), &[2, 4]);
+/// assert_eq!(
+///     parse_trees("13\n2").unwrap_err().to_string(),
+///     "day 8, line 2, column 1: expected 2 cells in every row (\"2\")"
+/// );
+pub fn parse_trees(text: &str) -> Result<Grid<u8>, AocError> {
+    Grid::parse(DAY, text, |c| c.to_digit(10).map(|x| x as u8))
 }
 
-/// ```
-/// # use advent_of_code_2022::day8::hidden;
-/// assert_eq!(hidden(&vec![3, 5, 2, 4, 1]), vec![false, false, true, false, false]);
-pub fn hidden(line: &Vec<u8>) -> Vec<bool> {
-    directional_hidden(line)
-        .iter()
-        .zip(directional_hidden(&reverse(line)).iter().rev())
-        .map(|(&l, &r)| l && r)
-        .collect()
+/// Whether a tree can be seen from outside the grid, because every tree
+/// between it and an edge is shorter.
+pub fn is_visible(trees: &Grid<u8>, cell: Cell) -> bool {
+    let height = trees[cell];
+    Direction::ORTHOGONAL
+        .into_iter()
+        .any(|direction| trees.ray(cell, direction).all(|&x| x < height))
 }
 
-pub fn directional_hidden(line: &Vec<u8>) -> Vec<bool> {
-    assert!(!line.is_empty());
-    let mut out = vec![false];
-    let mut max = line[0];
-    for &height in line.iter().skip(1) {
-        out.push(height <= max);
-        if height > max {
-            max = height;
+/// The number of trees that can be seen from a tree looking in `direction`,
+/// up to and including the first that is at least as tall.
+///
+/// ```
+/// # use advent_of_code_2022::day8::{parse_trees, viewing_distance};
+/// # use advent_of_code_2022::point::Direction;
+/// let trees = parse_trees("33549").unwrap();
+/// assert_eq!(viewing_distance(&trees, (2, 0), Direction::Left), 2);
+/// assert_eq!(viewing_distance(&trees, (2, 0), Direction::Right), 2);
+/// assert_eq!(viewing_distance(&trees, (4, 0), Direction::Right), 0);
+pub fn viewing_distance(
+    trees: &Grid<u8>,
+    cell: Cell,
+    direction: Direction,
+) -> usize {
+    let height = trees[cell];
+    let mut distance = 0;
+    for &x in trees.ray(cell, direction) {
+        distance += 1;
+        if x >= height {
+            break;
         }
     }
-    out
+    distance
 }
 
-pub fn view(line: &Vec<u8>) -> Vec<i32> {
-    directional_view(line)
-        .iter()
-        .zip(directional_view(&reverse(line)).iter().rev())
-        .map(|(l, r)| l * r)
-        .collect()
+pub fn scenic_score(trees: &Grid<u8>, cell: Cell) -> usize {
+    Direction::ORTHOGONAL
+        .into_iter()
+        .map(|direction| viewing_distance(trees, cell, direction))
+        .product()
 }
 
-/// ```
-/// # use advent_of_code_2022::day8::directional_view;
-/// assert_eq!(directional_view(&vec![3, 3, 5, 4, 9]), vec![0, 1, 2, 1, 4]);
-/// assert_eq!(directional_view(&vec![0, 1, 2, 3, 4]), vec![0, 1, 2, 3, 4]);
-/// assert_eq!(directional_view(&vec![0, 0, 0, 0, 0]), vec![0, 1, 1, 1, 1]);
-pub fn directional_view(line: &Vec<u8>) -> Vec<i32> {
-    assert!(!line.is_empty());
-    let mut out = vec![0];
-    line.iter().enumerate().skip(1).for_each(|(i, height)| {
-        out.push(
-            line.iter()
-                .take(i)
-                .skip(1)
-                .rev()
-                .take_while(|x| x < &height)
-                .count() as i32
-                + 1,
-        );
-    });
-    out
+pub fn count_visible(trees: &Grid<u8>) -> usize {
+    trees
+        .cells()
+        .filter(|&cell| is_visible(trees, cell))
+        .count()
+}
+
+pub fn best_view(trees: &Grid<u8>) -> usize {
+    trees
+        .cells()
+        .map(|cell| scenic_score(trees, cell))
+        .max()
+        .unwrap_or_default()
 }
 
 /// ```
-/// # use advent_of_code_2022::day8::reverse;
-/// assert_eq!(reverse(&[1, 2, 3, 4, 5]), vec![5, 4, 3, 2, 1])
-pub fn reverse<T: Clone>(v: &[T]) -> Vec<T> {
-    let mut reversed = Vec::new();
-    v.iter().rev().for_each(|x| reversed.push(x.clone()));
-    reversed
+/// # use advent_of_code_2022::day8::{solve_part1, solve_part2};
+/// let input = "30373\n25512\n65332\n33549\n35390";
+/// assert_eq!(solve_part1(input).unwrap(), 21);
+/// assert_eq!(solve_part2(input).unwrap(), 8);
+pub fn solve_part1(input: &str) -> Result<usize, AocError> {
+    Ok(count_visible(&parse_trees(input)?))
 }
 
-pub fn puzzle1(path: &str) -> Result<usize, Box<dyn Error>> {
-    let text = fs::read_to_string(path)?;
-    let vec_2d = vec_2d(&text);
-    let transposed_hidden =
-        transpose(&transpose(&vec_2d).iter().map(hidden).collect::<Vec<_>>());
-    Ok(vec_2d
-        .iter()
-        .map(hidden)
-        .zip(transposed_hidden.iter())
-        .map(|(r, c)| {
-            r.iter()
-                .zip(c.iter())
-                .map(|(&x, &y)| x && y)
-                .filter(|&x| !x)
-                .count()
-        })
-        .sum())
+pub fn solve_part2(input: &str) -> Result<usize, AocError> {
+    Ok(best_view(&parse_trees(input)?))
 }
 
-pub fn puzzle2(path: &str) -> Result<i32, Box<dyn Error>> {
-    let text = fs::read_to_string(path)?;
-    let vec_2d = vec_2d(&text);
-    let transposed_views =
-        transpose(&transpose(&vec_2d).iter().map(view).collect::<Vec<_>>());
-    Ok(vec_2d
-        .iter()
-        .map(view)
-        .zip(transposed_views.iter())
-        .map(|(r, c)| r.iter().zip(c.iter()).map(|(x, y)| x * y).max().unwrap())
-        .max()
-        .unwrap())
+pub fn puzzle1(path: &str) -> Result<usize, AocError> {
+    solve_part1(&fs::read_to_string(path)?)
+}
+
+pub fn puzzle2(path: &str) -> Result<usize, AocError> {
+    solve_part2(&fs::read_to_string(path)?)
+}
+
+pub struct Day8 {
+    trees: Grid<u8>,
+}
+
+impl Solution for Day8 {
+    fn parse(input: &str) -> Result<Self, AocError> {
+        Ok(Day8 {
+            trees: parse_trees(input)?,
+        })
+    }
+
+    fn part1(&self) -> Result<Answer, AocError> {
+        Ok(count_visible(&self.trees).into())
+    }
+
+    fn part2(&self) -> Result<Answer, AocError> {
+        Ok(best_view(&self.trees).into())
+    }
 }
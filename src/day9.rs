@@ -1,88 +1,544 @@
-use itertools::Itertools;
-use std::cmp::Ordering::{Equal, Greater, Less};
-use std::collections::HashSet;
-use std::error::Error;
+use crate::grid::Grid;
+use crate::point::{Direction, Point};
+use crate::{input, Answer, AocError, Solution};
+use std::collections::HashMap;
 use std::fs;
 
-#[derive(Default)]
-pub struct Tracker {
-    pos: Pos,
-    set: HashSet<Pos>,
+const DAY: u8 = 9;
+
+/// How far apart, counting diagonal steps, neighbouring knots can be before
+/// the rope pulls them together. With a slack of 1 knots must touch.
+pub const DEFAULT_SLACK: u32 = 1;
+
+/// Whether `knot` has been left further than `slack` behind by `leader`.
+pub fn is_in_tension(knot: Point, leader: Point, slack: u32) -> bool {
+    knot.chebyshev(leader) > slack
 }
 
-impl Tracker {
-    pub fn new(pos: Pos) -> Tracker {
-        let mut set = HashSet::new();
-        set.insert(pos.clone());
-        Tracker { pos, set }
+/// The largest area, in cells, that [`Visited`] keeps a single bitmap of:
+/// 32 MiB.
+pub const MAX_BITMAP_CELLS: usize = 1 << 28;
+
+/// The width and height of the tiles of a sparse [`Visited`].
+const TILE: i32 = 64;
+
+/// The positions a knot has been in, as a bit per cell. Within a known area
+/// that isn't too large this is one bitmap, otherwise it is a map of 64 by 64
+/// cell tiles, each added when a cell in it is first visited.
+#[derive(Debug, Clone)]
+pub enum Visited {
+    Bitmap {
+        min: Point,
+        width: usize,
+        height: usize,
+        bits: Vec<u64>,
+        len: usize,
+    },
+    /// Tiles keyed by their position divided by 64, with a `u64` of bits
+    /// per row.
+    Tiles {
+        tiles: HashMap<Point, [u64; TILE as usize]>,
+        len: usize,
+    },
+}
+
+impl Visited {
+    /// Tiles covering any position, none of which have been visited.
+    pub fn sparse() -> Visited {
+        Visited::Tiles {
+            tiles: HashMap::new(),
+            len: 0,
+        }
+    }
+
+    /// A bitmap over the area from `min` to `max`, or [`Visited::sparse`] if
+    /// the area holds more than [`MAX_BITMAP_CELLS`] cells.
+    ///
+    /// ```
+    /// # use advent_of_code_2022::{day9::Visited, point::Point};
+    /// let small = Visited::within(Point::new(-2, -2), Point::new(2, 2));
+    /// assert!(matches!(small, Visited::Bitmap { .. }));
+    /// let mut huge = Visited::within(Point::new(0, 0), Point::new(1 << 20, 1 << 20));
+    /// assert!(matches!(huge, Visited::Tiles { .. }));
+    /// assert!(huge.insert(Point::new(-70, 1 << 20)));
+    /// assert!(!huge.insert(Point::new(-70, 1 << 20)));
+    /// assert!(huge.contains(Point::new(-70, 1 << 20)));
+    /// assert_eq!(huge.positions().collect::<Vec<_>>(), vec![Point::new(-70, 1 << 20)]);
+    /// ```
+    pub fn within(min: Point, max: Point) -> Visited {
+        let width = max.x.abs_diff(min.x) as usize + 1;
+        let height = max.y.abs_diff(min.y) as usize + 1;
+        match width.checked_mul(height) {
+            Some(cells) if cells <= MAX_BITMAP_CELLS => Visited::Bitmap {
+                min,
+                width,
+                height,
+                bits: vec![0; cells.div_ceil(64)],
+                len: 0,
+            },
+            _ => Visited::sparse(),
+        }
     }
 
-    pub fn follow(&mut self, other: &Pos) {
-        self.pos.follow_set(other, &mut self.set);
+    /// Records `pos`, returning whether it is new.
+    ///
+    /// # Panics
+    ///
+    /// If `pos` is outside the area of a bitmap.
+    pub fn insert(&mut self, pos: Point) -> bool {
+        match self {
+            Visited::Bitmap {
+                min,
+                width,
+                height,
+                bits,
+                len,
+            } => {
+                let index = bit_index(*min, *width, *height, pos)
+                    .unwrap_or_else(|| panic!("{pos} is outside the bitmap"));
+                let (word, bit) = (index / 64, 1 << (index % 64));
+                let new = bits[word] & bit == 0;
+                bits[word] |= bit;
+                *len += usize::from(new);
+                new
+            }
+            Visited::Tiles { tiles, len } => {
+                let (tile, row, bit) = tile_index(pos);
+                let row =
+                    &mut tiles.entry(tile).or_insert([0; TILE as usize])[row];
+                let new = *row & bit == 0;
+                *row |= bit;
+                *len += usize::from(new);
+                new
+            }
+        }
+    }
+
+    pub fn contains(&self, pos: Point) -> bool {
+        match self {
+            Visited::Bitmap {
+                min,
+                width,
+                height,
+                bits,
+                ..
+            } => bit_index(*min, *width, *height, pos)
+                .is_some_and(|i| bits[i / 64] & (1 << (i % 64)) != 0),
+            Visited::Tiles { tiles, .. } => {
+                let (tile, row, bit) = tile_index(pos);
+                tiles.get(&tile).is_some_and(|rows| rows[row] & bit != 0)
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Visited::Bitmap { len, .. } | Visited::Tiles { len, .. } => *len,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Every visited position, in no particular order.
+    pub fn positions(&self) -> Box<dyn Iterator<Item = Point> + '_> {
+        match self {
+            Visited::Bitmap {
+                min, width, bits, ..
+            } => Box::new(
+                bits.iter()
+                    .enumerate()
+                    .filter(|(_, &word)| word != 0)
+                    .flat_map(|(i, &word)| {
+                        (0..64)
+                            .filter(move |bit| word & (1 << bit) != 0)
+                            .map(move |bit| i * 64 + bit)
+                    })
+                    .map(move |index| {
+                        Point::new(
+                            min.x + (index % width) as i32,
+                            min.y + (index / width) as i32,
+                        )
+                    }),
+            ),
+            Visited::Tiles { tiles, .. } => {
+                Box::new(tiles.iter().flat_map(|(tile, rows)| {
+                    (0..TILE).zip(rows).flat_map(move |(y, &row)| {
+                        (0..TILE).filter(move |x| row & (1 << x) != 0).map(
+                            move |x| {
+                                Point::new(tile.x * TILE + x, tile.y * TILE + y)
+                            },
+                        )
+                    })
+                }))
+            }
+        }
     }
 }
 
-#[derive(Eq, PartialEq, Clone, Hash, Debug, Default, Ord, PartialOrd)]
-pub struct Pos {
-    x: i32,
-    y: i32,
+/// The tile holding `pos`, its row within the tile, and its bit in the row.
+fn tile_index(pos: Point) -> (Point, usize, u64) {
+    let tile = Point::new(pos.x.div_euclid(TILE), pos.y.div_euclid(TILE));
+    let row = pos.y.rem_euclid(TILE).unsigned_abs() as usize;
+    (tile, row, 1 << pos.x.rem_euclid(TILE))
 }
 
-impl Pos {
-    pub fn do_move(&mut self, dir: &str, dist: i32) {
-        match dir {
-            "R" => self.x += dist,
-            "L" => self.x -= dist,
-            "U" => self.y += dist,
-            "D" => self.y -= dist,
-            _ => {}
+fn bit_index(
+    min: Point,
+    width: usize,
+    height: usize,
+    pos: Point,
+) -> Option<usize> {
+    let x = usize::try_from(pos.x.checked_sub(min.x)?).ok()?;
+    let y = usize::try_from(pos.y.checked_sub(min.y)?).ok()?;
+    (x < width && y < height).then_some(y * width + x)
+}
+
+/// A rope of knots starting on top of each other at the origin. The head is
+/// moved a step at a time and every other knot follows the one before it,
+/// with one knot, the tail unless another is chosen, remembering every
+/// position it has been in.
+///
+/// ```
+/// # use advent_of_code_2022::{day9::Rope, point::{Direction, Point}};
+/// let mut rope = Rope::new(3);
+/// rope.apply(Direction::Right, 4);
+/// assert_eq!(rope.head(), Point::new(4, 0));
+/// assert_eq!(rope.knots()[1], Point::new(3, 0));
+/// assert_eq!(rope.tail(), Point::new(2, 0));
+/// assert_eq!(rope.visited().len(), 3);
+/// let mut rope = Rope::new(3).tracking(1);
+/// rope.apply(Direction::Right, 4);
+/// assert_eq!(rope.visited().len(), 4);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Rope {
+    knots: Vec<Point>,
+    tracked: usize,
+    visited: Visited,
+    slack: u32,
+}
+
+impl Rope {
+    /// A rope of `knots` knots, including the head and the tail.
+    pub fn new(knots: usize) -> Rope {
+        Rope::with_slack(knots, DEFAULT_SLACK)
+    }
+
+    /// A rope whose knots only pull on each other once they are more than
+    /// `slack` apart.
+    ///
+    /// ```
+    /// # use advent_of_code_2022::{day9::Rope, point::{Direction, Point}};
+    /// let mut rope = Rope::with_slack(2, 3);
+    /// rope.apply(Direction::UpRight, 5);
+    /// assert_eq!(rope.tail(), Point::new(2, -2));
+    /// ```
+    pub fn with_slack(knots: usize, slack: u32) -> Rope {
+        assert!(knots > 0, "a rope needs at least one knot");
+        assert!(slack > 0, "knots can't pull closer than touching");
+        let mut visited = Visited::sparse();
+        visited.insert(Point::ORIGIN);
+        Rope {
+            knots: vec![Point::ORIGIN; knots],
+            tracked: knots - 1,
+            visited,
+            slack,
         }
     }
 
-    pub fn is_in_tension(&self, other: &Pos) -> bool {
-        self.x.abs_diff(other.x).pow(2) + self.y.abs_diff(other.y).pow(2) > 2
+    /// Remembers the positions of knot `knot`, where 0 is the head, instead
+    /// of the tail. The knots must still be at the start.
+    #[must_use]
+    pub fn tracking(mut self, knot: usize) -> Rope {
+        assert!(knot < self.knots.len(), "the rope has no knot {knot}");
+        self.tracked = knot;
+        self
+    }
+
+    /// Keeps track of visited positions with a single bitmap, for a rope
+    /// whose head won't leave the area from `min` to `max`, such as the
+    /// [`bounds`] of its moves. The knots must still be at the start. If the
+    /// area is too large for one bitmap, the tiles are kept.
+    #[must_use]
+    pub fn bounded(mut self, (min, max): (Point, Point)) -> Rope {
+        let mut visited = Visited::within(min, max);
+        visited.insert(Point::ORIGIN);
+        self.visited = visited;
+        self
+    }
+
+    pub fn knots(&self) -> &[Point] {
+        &self.knots
+    }
+
+    pub fn head(&self) -> Point {
+        self.knots[0]
     }
 
-    pub fn follow_set(&mut self, other: &Pos, set: &mut HashSet<Pos>) {
-        set.insert(self.clone());
-        if self.is_in_tension(other) {
-            match self.x.cmp(&other.x) {
-                Greater => self.x -= 1,
-                Less => self.x += 1,
-                Equal => {}
+    pub fn tail(&self) -> Point {
+        self.knots[self.knots.len() - 1]
+    }
+
+    /// The knot whose positions are remembered, where 0 is the head.
+    pub fn tracked(&self) -> usize {
+        self.tracked
+    }
+
+    /// Every position the [tracked](Rope::tracked) knot has been in.
+    pub fn visited(&self) -> &Visited {
+        &self.visited
+    }
+
+    /// Moves the head one step, which may be diagonal, then lets each knot
+    /// catch up with the one before it. A knot that has to move steps
+    /// diagonally if it is not in line with its leader. Each knot moves at
+    /// most one step, so this takes constant time per knot however long the
+    /// move is.
+    pub fn step(&mut self, dir: Direction) {
+        self.knots[0] += dir.delta();
+        if self.tracked == 0 {
+            self.visited.insert(self.knots[0]);
+        }
+        for i in 1..self.knots.len() {
+            let leader = self.knots[i - 1];
+            let knot = &mut self.knots[i];
+            if !is_in_tension(*knot, leader, self.slack) {
+                break;
             }
-            match self.y.cmp(&other.y) {
-                Greater => self.y -= 1,
-                Less => self.y += 1,
-                Equal => {}
+            *knot += (leader - *knot).signum();
+            if i == self.tracked {
+                self.visited.insert(*knot);
             }
-            self.follow_set(other, set);
         }
     }
-    pub fn follow(&mut self, other: &Pos) {
-        if self.is_in_tension(other) {
-            match self.x.cmp(&other.x) {
-                Greater => self.x -= 1,
-                Less => self.x += 1,
-                Equal => {}
-            }
-            match self.y.cmp(&other.y) {
-                Greater => self.y -= 1,
-                Less => self.y += 1,
-                Equal => {}
-            }
-            self.follow(other);
+
+    pub fn apply(&mut self, dir: Direction, dist: i32) {
+        for _ in 0..dist {
+            self.step(dir);
+        }
+    }
+}
+
+/// ```
+/// # use advent_of_code_2022::{day9::parse, point::Direction};
+/// assert_eq!(parse("R 4").unwrap(), (Direction::Right, 4));
+/// assert_eq!(parse("UR 2").unwrap(), (Direction::UpRight, 2));
+/// assert_eq!(
+///     parse("X 4").unwrap_err().to_string(),
+///     "day 9, line 1, column 1: expected U, D, L, R, a diagonal such as UR \
+///      or a compass direction (\"X\")"
+/// );
+pub fn parse(line: &str) -> Result<(Direction, i32), AocError> {
+    let mut args = line.split_whitespace();
+    let dir = args.next().unwrap_or_default();
+    let dir = dir
+        .parse()
+        .map_err(|err| AocError::parse(DAY, line, dir, format!("{err}")))?;
+    let dist = args.next().unwrap_or_default();
+    let dist = match dist.parse::<i32>() {
+        Ok(x) if x >= 0 => x,
+        _ => {
+            return Err(AocError::parse(DAY, line, dist, "expected a distance"))
         }
+    };
+    if let Some(extra) = args.next() {
+        return Err(AocError::parse(DAY, line, extra, "unexpected argument"));
     }
+    Ok((dir, dist))
 }
 
-pub fn parse(line: &str) -> Option<(String, i32)> {
-    let (dir, dist) = line.split_whitespace().collect_tuple()?;
-    let dist = dist.parse::<i32>().ok()?;
-    match dir {
-        "R" | "L" | "U" | "D" => Some((dir.to_string(), dist)),
-        _ => None,
+pub fn parse_moves(text: &str) -> Result<Vec<(Direction, i32)>, AocError> {
+    input::parse_lines(text, parse)
+}
+
+/// The number of positions the tail of a rope of `knots` knots visits.
+///
+/// ```
+/// # use advent_of_code_2022::{day9::tail_visits, point::Direction};
+/// let moves = [(Direction::Right, 2_000_000), (Direction::Left, 2_000_000)];
+/// assert_eq!(tail_visits(&moves, 2).unwrap(), 2_000_000);
+/// let moves = [(Direction::Right, 100_000), (Direction::Up, 100_000)];
+/// assert_eq!(tail_visits(&moves, 2).unwrap(), 199_999);
+/// assert_eq!(tail_visits(&moves, 10).unwrap(), 199_983);
+/// ```
+pub fn tail_visits(
+    moves: &[(Direction, i32)],
+    knots: usize,
+) -> Result<usize, AocError> {
+    let mut rope = Rope::new(knots).bounded(bounds(moves)?);
+    for &(dir, dist) in moves {
+        rope.apply(dir, dist);
     }
+    Ok(rope.visited().len())
+}
+
+/// The corners of the smallest area holding the start and every position the
+/// head passes through. The other knots never leave it, as each only moves
+/// towards the knot before it. Fails if the head would leave the range of a
+/// [`Point`].
+///
+/// ```
+/// # use advent_of_code_2022::{day9::bounds, point::{Direction, Point}};
+/// let moves = [(Direction::UpRight, 3), (Direction::Left, 5)];
+/// assert_eq!(
+///     bounds(&moves).unwrap(),
+///     (Point::new(-2, -3), Point::new(3, 0))
+/// );
+/// let moves = [(Direction::Right, i32::MAX), (Direction::Right, 1)];
+/// assert_eq!(
+///     bounds(&moves).unwrap_err().to_string(),
+///     "day 9: the moves go further than a position can hold"
+/// );
+/// ```
+pub fn bounds(moves: &[(Direction, i32)]) -> Result<(Point, Point), AocError> {
+    let (mut min, mut max) = (Point::ORIGIN, Point::ORIGIN);
+    let mut head = Point::ORIGIN;
+    for &(dir, dist) in moves {
+        let delta = dir.delta();
+        let go = |from: i32, by: i32| by.checked_mul(dist)?.checked_add(from);
+        let (Some(x), Some(y)) = (go(head.x, delta.x), go(head.y, delta.y))
+        else {
+            return Err(AocError::puzzle(
+                DAY,
+                "the moves go further than a position can hold",
+            ));
+        };
+        head = Point::new(x, y);
+        min = Point::new(min.x.min(head.x), min.y.min(head.y));
+        max = Point::new(max.x.max(head.x), max.y.max(head.y));
+    }
+    Ok((min, max))
+}
+
+/// How a knot is drawn: `H` for the head, `T` for the tail of a two knot
+/// rope, and otherwise its number. Knots past 9 are all drawn as `T`.
+pub fn knot_label(knot: usize, knots: usize) -> char {
+    match knot {
+        0 => 'H',
+        1 if knots == 2 => 'T',
+        1..=9 => char::from_digit(knot as u32, 10).unwrap(),
+        _ => 'T',
+    }
+}
+
+fn canvas((min, max): (Point, Point)) -> Grid<char> {
+    let width = max.x.abs_diff(min.x) as usize + 1;
+    let height = max.y.abs_diff(min.y) as usize + 1;
+    let mut grid = Grid::filled(width, height, '.');
+    plot(&mut grid, min, Point::ORIGIN, 's');
+    grid
+}
+
+/// Draws `c` at `point` if it is inside the grid whose top left is `min`.
+fn plot(grid: &mut Grid<char>, min: Point, point: Point, c: char) {
+    if point.x < min.x || point.y < min.y {
+        return;
+    }
+    let cell = (
+        point.x.abs_diff(min.x) as usize,
+        point.y.abs_diff(min.y) as usize,
+    );
+    if let Some(x) = grid.get_mut(cell) {
+        *x = c;
+    }
+}
+
+/// Draws the rope as in the puzzle, with `.` for empty cells and `s` for the
+/// start. Where knots overlap, the one nearest the head is shown.
+///
+/// ```
+/// # use advent_of_code_2022::day9::{bounds, draw_rope, parse_moves, Rope};
+/// let moves = parse_moves("R 4\nU 4\nL 3\nD 1\nR 4\nD 1\nL 5\nR 2").unwrap();
+/// let mut rope = Rope::new(2);
+/// rope.apply(moves[0].0, moves[0].1);
+/// assert_eq!(
+///     draw_rope(&rope, bounds(&moves).unwrap()).to_string(),
+///     "......\n......\n......\n......\ns..TH."
+/// );
+pub fn draw_rope(rope: &Rope, bounds: (Point, Point)) -> Grid<char> {
+    let mut grid = canvas(bounds);
+    let knots = rope.knots();
+    for (i, &knot) in knots.iter().enumerate().rev() {
+        plot(&mut grid, bounds.0, knot, knot_label(i, knots.len()));
+    }
+    grid
+}
+
+/// Draws the positions the tracked knot has visited as `#`, with `s` for the
+/// start.
+///
+/// ```
+/// # use advent_of_code_2022::day9::{bounds, draw_visited, parse_moves, Rope};
+/// let moves = parse_moves("R 4\nU 4\nL 3\nD 1\nR 4\nD 1\nL 5\nR 2").unwrap();
+/// let mut rope = Rope::new(2);
+/// for &(dir, dist) in &moves {
+///     rope.apply(dir, dist);
+/// }
+/// assert_eq!(
+///     draw_visited(&rope, bounds(&moves).unwrap()).to_string(),
+///     "..##..\n...##.\n.####.\n....#.\ns###.."
+/// );
+pub fn draw_visited(rope: &Rope, bounds: (Point, Point)) -> Grid<char> {
+    let mut grid = canvas(bounds);
+    for pos in rope.visited().positions() {
+        if pos != Point::ORIGIN {
+            plot(&mut grid, bounds.0, pos, '#');
+        }
+    }
+    grid
+}
+
+/// Runs `moves` on `rope`, passing `show` a drawing over `bounds` of the
+/// initial state and then of the rope after every move, or after every step
+/// of every move if `steps` is set. Each drawing is headed like the puzzle's,
+/// such as `== R 4 ==`. Returns the rope in its final state.
+pub fn animate<E>(
+    moves: &[(Direction, i32)],
+    mut rope: Rope,
+    bounds: (Point, Point),
+    steps: bool,
+    mut show: impl FnMut(&str) -> Result<(), E>,
+) -> Result<Rope, E> {
+    show(&format!(
+        "== Initial State ==\n\n{}\n",
+        draw_rope(&rope, bounds)
+    ))?;
+    for &(dir, dist) in moves {
+        if !steps {
+            rope.apply(dir, dist);
+            show(&format!(
+                "== {dir} {dist} ==\n\n{}\n",
+                draw_rope(&rope, bounds)
+            ))?;
+            continue;
+        }
+        for step in 1..=dist {
+            rope.step(dir);
+            show(&format!(
+                "== {dir} {dist}, step {step} ==\n\n{}\n",
+                draw_rope(&rope, bounds)
+            ))?;
+        }
+    }
+    Ok(rope)
+}
+
+/// ```
+/// # use advent_of_code_2022::day9::solve_part1;
+/// let input = "R 4\nU 4\nL 3\nD 1\nR 4\nD 1\nL 5\nR 2";
+/// assert_eq!(solve_part1(input).unwrap(), 13usize);
+pub fn solve_part1(input: &str) -> Result<usize, AocError> {
+    tail_visits(&parse_moves(input)?, 2)
+}
+
+/// ```
+/// # use advent_of_code_2022::day9::solve_part2;
+/// let input = "R 5\nU 8\nL 8\nD 3\nR 17\nD 10\nL 25\nU 20";
+/// assert_eq!(solve_part2(input).unwrap(), 36usize);
+pub fn solve_part2(input: &str) -> Result<usize, AocError> {
+    tail_visits(&parse_moves(input)?, 10)
 }
 
 /// ```
@@ -90,15 +546,8 @@ pub fn parse(line: &str) -> Option<(String, i32)> {
 /// assert_eq!(puzzle1("day9a.txt").unwrap(), 10usize);
 /// assert_eq!(puzzle1("day9b.txt").unwrap(), 13usize);
 /// assert_eq!(puzzle1("day9.txt").unwrap(), 6464usize);
-pub fn puzzle1(path: &str) -> Result<usize, Box<dyn Error>> {
-    let mut head = Pos::default();
-    let mut tail = Tracker::default();
-    for line in fs::read_to_string(path)?.lines().map(parse) {
-        let (dir, dist) = line.ok_or("Invalid move")?;
-        head.do_move(&dir, dist);
-        tail.follow(&head);
-    }
-    Ok(tail.set.len())
+pub fn puzzle1(path: &str) -> Result<usize, AocError> {
+    solve_part1(&fs::read_to_string(path)?)
 }
 
 /// ```
@@ -106,22 +555,26 @@ pub fn puzzle1(path: &str) -> Result<usize, Box<dyn Error>> {
 /// assert_eq!(puzzle2("day9b.txt").unwrap(), 1usize);
 /// assert_eq!(puzzle2("day9c.txt").unwrap(), 36usize);
 /// assert_eq!(puzzle2("day9.txt").unwrap(), 2604usize);
-pub fn puzzle2(path: &str) -> Result<usize, Box<dyn Error>> {
-    let mut head = Vec::new();
-    for _ in 0..9 {
-        head.push(Pos::default());
-    }
-    let mut tail = Tracker::default();
-    for line in fs::read_to_string(path)?.lines().map(parse) {
-        let (dir, dist) = line.ok_or("Invalid move")?;
-        for _ in 0..dist {
-            head[0].do_move(&dir, 1);
-            for i in 1..9 {
-                let temp = head[i - 1].clone();
-                head[i].follow(&temp);
-            }
-            tail.follow(&head[8]);
-        }
+pub fn puzzle2(path: &str) -> Result<usize, AocError> {
+    solve_part2(&fs::read_to_string(path)?)
+}
+
+pub struct Day9 {
+    moves: Vec<(Direction, i32)>,
+}
+
+impl Solution for Day9 {
+    fn parse(input: &str) -> Result<Self, AocError> {
+        Ok(Day9 {
+            moves: parse_moves(input)?,
+        })
+    }
+
+    fn part1(&self) -> Result<Answer, AocError> {
+        Ok(tail_visits(&self.moves, 2)?.into())
+    }
+
+    fn part2(&self) -> Result<Answer, AocError> {
+        Ok(tail_visits(&self.moves, 10)?.into())
     }
-    Ok(tail.set.len())
 }
@@ -0,0 +1,69 @@
+use crate::input;
+use std::{io, str::Utf8Error};
+
+#[derive(Debug, thiserror::Error)]
+pub enum AocError {
+    #[error("day {day}, line {line}, column {column}: {message} ({text:?})")]
+    Parse {
+        day: u8,
+        line: usize,
+        column: usize,
+        text: String,
+        message: String,
+    },
+    #[error("day {day}: {message}")]
+    Puzzle { day: u8, message: String },
+    #[error("{0}")]
+    Io(#[from] io::Error),
+    #[error("input is not valid UTF-8: {0}")]
+    Utf8(#[from] Utf8Error),
+}
+
+impl AocError {
+    /// An error at `token` within `line`. It is numbered as line 1 until the
+    /// caller places it with [`AocError::offset_lines`]. An empty `token`,
+    /// such as a missing argument, reports the whole line and points past its
+    /// end.
+    ///
+    /// ```
+    /// # use advent_of_code_2022::AocError;
+    /// let line = "move 1 from x to 2";
+    /// let err = AocError::parse(5, line, &line[12..13], "expected a number");
+    /// assert_eq!(
+    ///     err.offset_lines(9).to_string(),
+    ///     "day 5, line 10, column 13: expected a number (\"x\")"
+    /// );
+    /// ```
+    pub fn parse(
+        day: u8,
+        line: &str,
+        token: &str,
+        message: impl Into<String>,
+    ) -> AocError {
+        AocError::Parse {
+            day,
+            line: 1,
+            column: input::column_of(line, token),
+            text: if token.is_empty() { line } else { token }.to_string(),
+            message: message.into(),
+        }
+    }
+
+    /// An error that isn't tied to a position in the input.
+    pub fn puzzle(day: u8, message: impl Into<String>) -> AocError {
+        AocError::Puzzle {
+            day,
+            message: message.into(),
+        }
+    }
+
+    /// Moves a parse error down by `lines`, for errors raised while parsing
+    /// part of a larger input.
+    #[must_use]
+    pub fn offset_lines(mut self, lines: usize) -> AocError {
+        if let AocError::Parse { line, .. } = &mut self {
+            *line += lines;
+        }
+        self
+    }
+}
@@ -0,0 +1,290 @@
+use crate::point::{Direction, Point};
+use crate::{input, AocError};
+use std::{fmt, ops::Index};
+
+/// A position in a grid, as `(x, y)` with `(0, 0)` at the top left.
+pub type Cell = (usize, usize);
+
+/// A rectangle of cells stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from rows of equal length. Returns `None` if the rows
+    /// differ in length.
+    ///
+    /// ```
+    /// # use advent_of_code_2022::grid::Grid;
+    /// let grid = Grid::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6]]).unwrap();
+    /// assert_eq!((grid.width(), grid.height()), (3, 2));
+    /// assert_eq!(grid[(2, 1)], 6);
+    /// assert!(Grid::from_rows(vec![vec![1, 2], vec![3]]).is_none());
+    /// ```
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Grid<T>> {
+        let width = rows.first().map_or(0, Vec::len);
+        let height = rows.len();
+        if rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+        Some(Grid {
+            width,
+            height,
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    /// A `width` by `height` grid with every cell set to `value`.
+    pub fn filled(width: usize, height: usize, value: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Parses one cell per character, one row per line. Errors are reported
+    /// against `day` at the character or row that is wrong.
+    ///
+    /// ```
+    /// # use advent_of_code_2022::grid::Grid;
+    /// let grid = Grid::parse(8, "12\n34", |c| c.to_digit(10)).unwrap();
+    /// assert_eq!(grid.row(1), &[3, 4]);
+    /// assert_eq!(
+    ///     Grid::parse(8, "12\n3x", |c| c.to_digit(10))
+    ///         .unwrap_err()
+    ///         .to_string(),
+    ///     "day 8, line 2, column 2: unexpected character (\"x\")"
+    /// );
+    /// ```
+    pub fn parse(
+        day: u8,
+        text: &str,
+        mut cell: impl FnMut(char) -> Option<T>,
+    ) -> Result<Grid<T>, AocError> {
+        let mut width = None;
+        let rows = input::parse_lines(text, |row| {
+            let cells = row
+                .char_indices()
+                .map(|(i, c)| {
+                    cell(c).ok_or_else(|| {
+                        AocError::parse(
+                            day,
+                            row,
+                            &row[i..i + c.len_utf8()],
+                            "unexpected character",
+                        )
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            if cells.is_empty() {
+                return Err(AocError::parse(day, row, "", "expected a row"));
+            }
+            let width = *width.get_or_insert(cells.len());
+            if width != cells.len() {
+                return Err(AocError::parse(
+                    day,
+                    row,
+                    row,
+                    format!("expected {width} cells in every row"),
+                ));
+            }
+            Ok(cells)
+        })?;
+        if rows.is_empty() {
+            return Err(AocError::puzzle(day, "empty grid"));
+        }
+        Ok(Grid::from_rows(rows).expect("rows are checked when parsed"))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, (x, y): Cell) -> Option<&T> {
+        (x < self.width && y < self.height)
+            .then(|| &self.cells[y * self.width + x])
+    }
+
+    pub fn get_mut(&mut self, (x, y): Cell) -> Option<&mut T> {
+        (x < self.width && y < self.height)
+            .then(|| &mut self.cells[y * self.width + x])
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    /// ```
+    /// # use advent_of_code_2022::grid::Grid;
+    /// let grid = Grid::from_rows(vec![vec![1, 2], vec![3, 4]]).unwrap();
+    /// assert_eq!(grid.column(1).collect::<Vec<_>>(), vec![&2, &4]);
+    /// ```
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {x} is outside the grid");
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    /// Each row in turn. A grid with no columns still has `height` rows,
+    /// they're just empty.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(|y| self.row(y))
+    }
+
+    /// Every position in the grid, row by row.
+    pub fn cells(&self) -> impl Iterator<Item = Cell> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// The position `delta` away from `cell`, if it is in the grid.
+    pub fn step(&self, (x, y): Cell, delta: Point) -> Option<Cell> {
+        let x = x
+            .checked_add_signed(delta.x as isize)
+            .filter(|&x| x < self.width)?;
+        let y = y
+            .checked_add_signed(delta.y as isize)
+            .filter(|&y| y < self.height)?;
+        Some((x, y))
+    }
+
+    /// The positions from `cell`, not including it, to the edge of the grid in
+    /// `direction`.
+    ///
+    /// ```
+    /// # use advent_of_code_2022::{grid::Grid, point::Direction};
+    /// let grid = Grid::from_rows(vec![vec![0; 4]; 3]).unwrap();
+    /// let left: Vec<_> = grid.walk((2, 1), Direction::Left).collect();
+    /// assert_eq!(left, vec![(1, 1), (0, 1)]);
+    /// assert_eq!(grid.walk((2, 0), Direction::Up).count(), 0);
+    /// ```
+    pub fn walk(
+        &self,
+        cell: Cell,
+        direction: Direction,
+    ) -> impl Iterator<Item = Cell> + '_ {
+        let delta = direction.delta();
+        let mut cell = Some(cell);
+        std::iter::from_fn(move || {
+            cell = self.step(cell?, delta);
+            cell
+        })
+    }
+
+    /// The values from `cell`, not including it, to the edge of the grid in
+    /// `direction`.
+    pub fn ray(
+        &self,
+        cell: Cell,
+        direction: Direction,
+    ) -> impl Iterator<Item = &T> + '_ {
+        self.walk(cell, direction).map(|cell| &self[cell])
+    }
+
+    /// The up to four cells that share an edge with `cell`.
+    pub fn neighbours4(&self, cell: Cell) -> impl Iterator<Item = Cell> + '_ {
+        Direction::ORTHOGONAL
+            .into_iter()
+            .filter_map(move |direction| self.step(cell, direction.delta()))
+    }
+
+    /// The up to eight cells that share an edge or corner with `cell`.
+    ///
+    /// ```
+    /// # use advent_of_code_2022::grid::Grid;
+    /// let grid = Grid::from_rows(vec![vec![0; 3]; 3]).unwrap();
+    /// assert_eq!(grid.neighbours4((0, 0)).count(), 2);
+    /// assert_eq!(grid.neighbours8((0, 0)).count(), 3);
+    /// assert_eq!(grid.neighbours8((1, 1)).count(), 8);
+    /// ```
+    pub fn neighbours8(&self, cell: Cell) -> impl Iterator<Item = Cell> + '_ {
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |direction| self.step(cell, direction.delta()))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T> Index<Cell> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, cell: Cell) -> &T {
+        self.get(cell)
+            .unwrap_or_else(|| panic!("{cell:?} is outside the grid"))
+    }
+}
+
+/// Prints each row on its own line with no separator between cells.
+///
+/// ```
+/// # use advent_of_code_2022::grid::Grid;
+/// let grid = Grid::parse(8, "12\n34", |c| c.to_digit(10)).unwrap();
+/// assert_eq!(grid.to_string(), "12\n34");
+/// ```
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::grid::Grid;
+    use crate::point::Direction;
+
+    #[test]
+    fn can_walk_every_direction() {
+        let grid = Grid::parse(0, "abc\ndef\nghi", Some).unwrap();
+        let ray = |direction| grid.ray((1, 1), direction).collect::<String>();
+        assert_eq!(ray(Direction::Up), "b");
+        assert_eq!(ray(Direction::Down), "h");
+        assert_eq!(ray(Direction::Left), "d");
+        assert_eq!(ray(Direction::Right), "f");
+        assert_eq!(grid.column(2).collect::<String>(), "cfi");
+    }
+
+    #[test]
+    fn can_print_grid_without_columns() {
+        assert_eq!(Grid::<u8>::from_rows(vec![]).unwrap().to_string(), "");
+        let grid = Grid::filled(0, 3, 0_u8);
+        assert_eq!(grid.rows().filter(|row| row.is_empty()).count(), 3);
+        assert_eq!(grid.to_string(), "\n\n");
+    }
+
+    #[test]
+    fn cannot_parse_ragged_grid() {
+        assert_eq!(
+            Grid::parse(0, "ab\nc", Some).unwrap_err().to_string(),
+            "day 0, line 2, column 1: expected 2 cells in every row (\"c\")"
+        );
+        assert_eq!(
+            Grid::parse(0, "", Some).unwrap_err().to_string(),
+            "day 0: empty grid"
+        );
+    }
+}
@@ -0,0 +1,80 @@
+use crate::AocError;
+
+/// Splits text into sections separated by blank lines. Lines holding only
+/// whitespace count as blank, so LF and CRLF input split the same way, and
+/// trailing whitespace is trimmed from the end of each section.
+///
+/// ```
+/// # use advent_of_code_2022::input::sections;
+/// assert_eq!(sections("1\n2\n\n3\n"), vec!["1\n2", "3"]);
+/// assert_eq!(sections("1\r\n2\r\n\r\n3\r\n"), vec!["1\r\n2", "3"]);
+/// assert_eq!(sections("\n  a\n \t\n\n b \n\n"), vec!["  a", " b"]);
+/// ```
+pub fn sections(text: &str) -> Vec<&str> {
+    let mut sections = Vec::new();
+    let mut start = None;
+    let mut end = 0;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        if line.trim().is_empty() {
+            if let Some(start) = start.take() {
+                sections.push(&text[start..end]);
+            }
+        } else {
+            start.get_or_insert(offset);
+            end = offset + line.trim_end().len();
+        }
+        offset += line.len();
+    }
+    if let Some(start) = start {
+        sections.push(&text[start..end]);
+    }
+    sections
+}
+
+/// Parses every line of `text`, numbering errors by the line they came from.
+pub fn parse_lines<'a, T>(
+    text: &'a str,
+    mut parse: impl FnMut(&'a str) -> Result<T, AocError>,
+) -> Result<Vec<T>, AocError> {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| parse(line).map_err(|err| err.offset_lines(i)))
+        .collect()
+}
+
+/// The number of lines in `text` before `part`, a slice of it, starts.
+///
+/// ```
+/// # use advent_of_code_2022::input::{line_of, sections};
+/// let text = "a\nb\n\nc\nd";
+/// assert_eq!(line_of(text, sections(text)[1]), 3);
+/// ```
+pub fn line_of(text: &str, part: &str) -> usize {
+    match offset_of(text, part) {
+        Some(offset) => text[..offset].matches('\n').count(),
+        None => 0,
+    }
+}
+
+/// The 1-based column at which `part`, a slice of `line`, starts. Anything
+/// that isn't a slice of the line points just past its end.
+///
+/// ```
+/// # use advent_of_code_2022::input::column_of;
+/// let line = "addx 15";
+/// assert_eq!(column_of(line, &line[5..]), 6);
+/// assert_eq!(column_of(line, ""), 8);
+/// ```
+pub fn column_of(line: &str, part: &str) -> usize {
+    match offset_of(line, part) {
+        Some(offset) => line[..offset].chars().count() + 1,
+        None => line.chars().count() + 1,
+    }
+}
+
+fn offset_of(text: &str, part: &str) -> Option<usize> {
+    let start = text.as_ptr() as usize;
+    let offset = (part.as_ptr() as usize).checked_sub(start)?;
+    (!part.is_empty() && offset + part.len() <= text.len()).then_some(offset)
+}
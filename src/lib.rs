@@ -6,6 +6,8 @@
 #![allow(clippy::cast_possible_wrap)]
 #![allow(clippy::cast_possible_truncation)]
 
+pub mod bench;
+pub mod cli;
 pub mod day1;
 pub mod day10;
 pub mod day11;
@@ -17,3 +19,36 @@ pub mod day6;
 pub mod day7;
 pub mod day8;
 pub mod day9;
+pub mod error;
+pub mod grid;
+pub mod input;
+pub mod point;
+pub mod solution;
+pub mod verify;
+
+pub use error::AocError;
+pub use solution::{Answer, Day, Solution};
+
+/// Every solved day, in order.
+pub const DAYS: [Day; 11] = [
+    Day::new::<day1::Day1>(1),
+    Day::new::<day2::Day2>(2),
+    Day::new::<day3::Day3>(3),
+    Day::new::<day4::Day4>(4),
+    Day::new::<day5::Day5>(5),
+    Day::new::<day6::Day6>(6),
+    Day::new::<day7::Day7>(7),
+    Day::new::<day8::Day8>(8),
+    Day::new::<day9::Day9>(9),
+    Day::new::<day10::Day10>(10),
+    Day::new::<day11::Day11>(11),
+];
+
+/// ```
+/// # use advent_of_code_2022::day;
+/// assert_eq!(day(7).unwrap().number, 7);
+/// assert!(day(25).is_none());
+/// ```
+pub fn day(number: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|day| day.number == number)
+}
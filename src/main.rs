@@ -1,27 +1,78 @@
-use advent_of_code_2022::*;
+use advent_of_code_2022::{
+    bench::Report,
+    cli::{self, Command},
+    verify::Answers,
+};
+use std::{env, process};
 
 fn main() {
-    println!("Day 1 Puzzle 1 {:?}", day1::puzzle1("day1.txt"));
-    println!("Day 1 Puzzle 2 {:?}", day1::puzzle2("day1.txt"));
-    println!("Day 2 Puzzle 1 {:?}", day2::puzzle1("day2.txt"));
-    println!("Day 2 Puzzle 2 {:?}", day2::puzzle2("day2.txt"));
-    println!("Day 3 Puzzle 1 {:?}", day3::puzzle1("day3.txt"));
-    println!("Day 3 Puzzle 2 {:?}", day3::puzzle2("day3.txt"));
-    println!("Day 4 Puzzle 1 {:?}", day4::puzzle1("day4.txt"));
-    println!("Day 4 Puzzle 2 {:?}", day4::puzzle2("day4.txt"));
-    println!("Day 5 Puzzle 1 {:?}", day5::puzzle1("day5.txt"));
-    println!("Day 5 Puzzle 2 {:?}", day5::puzzle2("day5.txt"));
-    println!("Day 6 Puzzle 1 {:?}", day6::puzzle1("day6.txt"));
-    println!("Day 6 Puzzle 2 {:?}", day6::puzzle2("day6.txt"));
-    println!("Day 7 Puzzle 1 {:?}", day7::puzzle1("day7.txt"));
-    println!("Day 7 Puzzle 2 {:?}", day7::puzzle2("day7.txt"));
-    println!("Day 8 Puzzle 1 {:?}", day8::puzzle1("day8.txt"));
-    println!("Day 8 Puzzle 2 {:?}", day8::puzzle2("day8.txt"));
-    println!("Day 9 Puzzle 1 {:?}", day9::puzzle1("day9.txt"));
-    println!("Day 9 Puzzle 2 {:?}", day9::puzzle2("day9.txt"));
-    println!("Day 10 Puzzle 1 {:?}", day10::puzzle1("day10.txt"));
-    println!("Day 10 Puzzle 2:");
-    day10::puzzle2("day10.txt").unwrap();
-    println!("Day 11 Puzzle 1 {:?}", day11::puzzle1("day11.txt"));
-    println!("Day 11 Puzzle 2 {:?}", day11::puzzle2("day11.txt"));
+    match cli::parse_args(env::args().skip(1)) {
+        Ok(Command::Help) => println!("{}", cli::USAGE),
+        Ok(Command::Run(selection)) => {
+            if !cli::run(&selection) {
+                process::exit(1);
+            }
+        }
+        Ok(Command::Verify { selection, answers }) => {
+            let answers = match Answers::load(&answers) {
+                Ok(answers) => answers,
+                Err(err) => {
+                    eprintln!("{}: {err}", answers.display());
+                    process::exit(2);
+                }
+            };
+            if !cli::verify(&selection, &answers) {
+                process::exit(1);
+            }
+        }
+        Ok(Command::Bench {
+            selection,
+            iterations,
+            save,
+            baseline,
+        }) => {
+            let baseline = baseline.map(|path| match Report::load(&path) {
+                Ok(report) => report,
+                Err(err) => {
+                    eprintln!("{}: {err}", path.display());
+                    process::exit(2);
+                }
+            });
+            let (report, success) =
+                cli::bench(&selection, iterations, baseline.as_ref());
+            if let Some(path) = save {
+                if let Err(err) = report.save(&path) {
+                    eprintln!("{}: {err}", path.display());
+                    process::exit(2);
+                }
+            }
+            if !success {
+                process::exit(1);
+            }
+        }
+        Ok(Command::Rope(options)) => {
+            if !cli::rope(&options) {
+                process::exit(1);
+            }
+        }
+        Ok(Command::Trace(options)) => {
+            if !cli::trace(&options) {
+                process::exit(1);
+            }
+        }
+        Ok(Command::Draw(picture)) => {
+            if !cli::draw(&picture) {
+                process::exit(1);
+            }
+        }
+        Ok(Command::Monkeys(options)) => {
+            if !cli::monkeys(&options) {
+                process::exit(1);
+            }
+        }
+        Err(err) => {
+            eprintln!("{err}\n\n{}", cli::USAGE);
+            process::exit(2);
+        }
+    }
 }
@@ -0,0 +1,240 @@
+use std::{
+    fmt,
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+    str::FromStr,
+};
+
+/// A position or offset on an unbounded plane. Like [`crate::grid::Grid`],
+/// y grows downwards, so [`Direction::Up`] is negative y.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point {
+    pub const ORIGIN: Point = Point { x: 0, y: 0 };
+
+    pub const fn new(x: i32, y: i32) -> Point {
+        Point { x, y }
+    }
+
+    /// The distance moving only along rows and columns.
+    ///
+    /// ```
+    /// # use advent_of_code_2022::point::Point;
+    /// assert_eq!(Point::new(1, 2).manhattan(Point::new(4, -2)), 7);
+    /// ```
+    pub fn manhattan(self, other: Point) -> u32 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// The distance when diagonal steps are allowed, so every point touching
+    /// this one, corners included, is 1 away.
+    ///
+    /// ```
+    /// # use advent_of_code_2022::point::Point;
+    /// assert_eq!(Point::new(1, 2).chebyshev(Point::new(4, -2)), 4);
+    /// assert_eq!(Point::new(0, 0).chebyshev(Point::new(1, 1)), 1);
+    /// ```
+    pub fn chebyshev(self, other: Point) -> u32 {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+    }
+
+    /// Each coordinate reduced to -1, 0 or 1, giving a single step towards
+    /// this offset.
+    ///
+    /// ```
+    /// # use advent_of_code_2022::point::Point;
+    /// assert_eq!(Point::new(-5, 0).signum(), Point::new(-1, 0));
+    /// ```
+    #[must_use]
+    pub fn signum(self) -> Point {
+        Point::new(self.x.signum(), self.y.signum())
+    }
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Mul<i32> for Point {
+    type Output = Point;
+
+    fn mul(self, scale: i32) -> Point {
+        Point::new(self.x * scale, self.y * scale)
+    }
+}
+
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Point {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, other: Point) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, other: Point) {
+        *self = *self - other;
+    }
+}
+
+/// One of the four directions along rows and columns, or one of the four
+/// diagonals between them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
+}
+
+impl Direction {
+    /// The directions along rows and columns.
+    pub const ORTHOGONAL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+    ];
+
+    pub const DIAGONALS: [Direction; 4] = [
+        Direction::UpLeft,
+        Direction::UpRight,
+        Direction::DownLeft,
+        Direction::DownRight,
+    ];
+
+    /// Every direction, orthogonal ones first.
+    pub const ALL: [Direction; 8] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+        Direction::UpLeft,
+        Direction::UpRight,
+        Direction::DownLeft,
+        Direction::DownRight,
+    ];
+
+    /// The offset of one step in this direction.
+    ///
+    /// ```
+    /// # use advent_of_code_2022::point::{Direction, Point};
+    /// let moved = Point::ORIGIN + Direction::Up.delta() * 3;
+    /// assert_eq!(moved, Point::new(0, -3));
+    /// ```
+    pub fn delta(self) -> Point {
+        match self {
+            Direction::Up => Point::new(0, -1),
+            Direction::Down => Point::new(0, 1),
+            Direction::Left => Point::new(-1, 0),
+            Direction::Right => Point::new(1, 0),
+            Direction::UpLeft => Point::new(-1, -1),
+            Direction::UpRight => Point::new(1, -1),
+            Direction::DownLeft => Point::new(-1, 1),
+            Direction::DownRight => Point::new(1, 1),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+#[error("expected U, D, L, R, a diagonal such as UR or a compass direction")]
+pub struct ParseDirectionError;
+
+/// Reads the single letters used by puzzles, `U`, `D`, `L` and `R`, pairs of
+/// them for diagonals, such as `UR`, or compass names, `N`, `SW`, `north` and
+/// so on, in any case.
+///
+/// ```
+/// # use advent_of_code_2022::point::Direction;
+/// assert_eq!("U".parse(), Ok(Direction::Up));
+/// assert_eq!("west".parse(), Ok(Direction::Left));
+/// assert_eq!("DL".parse(), Ok(Direction::DownLeft));
+/// assert_eq!("northeast".parse(), Ok(Direction::UpRight));
+/// assert!("X".parse::<Direction>().is_err());
+/// ```
+impl FromStr for Direction {
+    type Err = ParseDirectionError;
+
+    fn from_str(s: &str) -> Result<Direction, ParseDirectionError> {
+        match s.to_ascii_lowercase().as_str() {
+            "u" | "up" | "n" | "north" => Ok(Direction::Up),
+            "d" | "down" | "s" | "south" => Ok(Direction::Down),
+            "l" | "left" | "w" | "west" => Ok(Direction::Left),
+            "r" | "right" | "e" | "east" => Ok(Direction::Right),
+            "ul" | "lu" | "nw" | "northwest" => Ok(Direction::UpLeft),
+            "ur" | "ru" | "ne" | "northeast" => Ok(Direction::UpRight),
+            "dl" | "ld" | "sw" | "southwest" => Ok(Direction::DownLeft),
+            "dr" | "rd" | "se" | "southeast" => Ok(Direction::DownRight),
+            _ => Err(ParseDirectionError),
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Direction::Up => "U",
+            Direction::Down => "D",
+            Direction::Left => "L",
+            Direction::Right => "R",
+            Direction::UpLeft => "UL",
+            Direction::UpRight => "UR",
+            Direction::DownLeft => "DL",
+            Direction::DownRight => "DR",
+        })
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::point::{Direction, Point};
+
+    #[test]
+    fn can_do_arithmetic() {
+        let mut p = Point::new(2, 3);
+        p += Point::new(1, -1);
+        assert_eq!(p, Point::new(3, 2));
+        p -= Direction::Right.delta() * 3;
+        assert_eq!(p, Point::new(0, 2));
+        assert_eq!(-p, Point::new(0, -2));
+    }
+
+    #[test]
+    fn can_round_trip_directions() {
+        for direction in Direction::ALL {
+            assert_eq!(direction.to_string().parse(), Ok(direction));
+            assert_eq!(-direction.delta() + direction.delta(), Point::ORIGIN);
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use crate::AocError;
+use serde::Serialize;
+use std::{fmt, num::TryFromIntError, str};
+
+/// The answer to one part of a puzzle. Integers are wide enough to hold any
+/// `i64` or `usize`.
+///
+/// ```
+/// # use advent_of_code_2022::Answer;
+/// assert_eq!(Answer::from(42usize).to_string(), "42");
+/// assert_eq!(Answer::from(usize::MAX).to_string(), usize::MAX.to_string());
+/// assert_eq!(Answer::from("NTWZZWHFV").to_string(), "NTWZZWHFV");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum Answer {
+    Int(i128),
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Int(x) => write!(f, "{x}"),
+            Answer::Text(x) => write!(f, "{x}"),
+        }
+    }
+}
+
+impl From<i32> for Answer {
+    fn from(x: i32) -> Self {
+        Answer::Int(x.into())
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(x: i64) -> Self {
+        Answer::Int(x.into())
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(x: usize) -> Self {
+        Answer::Int(x.try_into().expect("a usize fits in an i128"))
+    }
+}
+
+impl TryFrom<u128> for Answer {
+    type Error = TryFromIntError;
+
+    fn try_from(x: u128) -> Result<Self, Self::Error> {
+        Ok(Answer::Int(x.try_into()?))
+    }
+}
+
+impl From<String> for Answer {
+    fn from(x: String) -> Self {
+        Answer::Text(x)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(x: &str) -> Self {
+        Answer::Text(x.to_string())
+    }
+}
+
+/// A day's puzzle: the input is parsed once and both parts are answered from
+/// the parsed form.
+pub trait Solution {
+    fn parse(input: &str) -> Result<Self, AocError>
+    where
+        Self: Sized;
+
+    fn part1(&self) -> Result<Answer, AocError>;
+
+    fn part2(&self) -> Result<Answer, AocError>;
+}
+
+pub type Parser = fn(&str) -> Result<Box<dyn Solution>, AocError>;
+
+/// An entry in the registry of solved days.
+#[derive(Clone, Copy)]
+pub struct Day {
+    pub number: u8,
+    pub parse: Parser,
+}
+
+impl Day {
+    pub const fn new<S: Solution + 'static>(number: u8) -> Day {
+        Day {
+            number,
+            parse: parse_boxed::<S>,
+        }
+    }
+
+    /// The input file checked into the repository for this day.
+    pub fn default_input(&self) -> String {
+        format!("day{}.txt", self.number)
+    }
+
+    pub fn run(&self, input: &str, part: u8) -> Result<Answer, AocError> {
+        self.run_parsed(&*(self.parse)(input)?, part)
+    }
+
+    /// Runs a part of a solution that has already been parsed.
+    pub fn run_parsed(
+        &self,
+        solution: &dyn Solution,
+        part: u8,
+    ) -> Result<Answer, AocError> {
+        match part {
+            1 => solution.part1(),
+            2 => solution.part2(),
+            x => Err(AocError::puzzle(self.number, format!("no part {x}"))),
+        }
+    }
+
+    /// Runs a part against raw input, such as a network buffer.
+    ///
+    /// ```
+    /// # use advent_of_code_2022::day;
+    /// let answer = day(6).unwrap().run_bytes(b"bvwbjplbgvbhsrlpgdmjqwftvncz", 1);
+    /// assert_eq!(answer.unwrap().to_string(), "5");
+    /// ```
+    pub fn run_bytes(
+        &self,
+        input: &[u8],
+        part: u8,
+    ) -> Result<Answer, AocError> {
+        self.run(str::from_utf8(input)?, part)
+    }
+}
+
+fn parse_boxed<S: Solution + 'static>(
+    input: &str,
+) -> Result<Box<dyn Solution>, AocError> {
+    Ok(Box::new(S::parse(input)?))
+}
@@ -0,0 +1,206 @@
+use crate::{Answer, AocError, Day};
+use itertools::{EitherOrBoth, Itertools};
+use std::{collections::BTreeMap, fmt, fs, io, path::Path};
+
+/// The manifest checked into the repository root.
+pub const DEFAULT_ANSWERS: &str = "answers.toml";
+
+#[derive(Debug, thiserror::Error)]
+pub enum ManifestError {
+    #[error("could not read answers: {0}")]
+    Io(#[from] io::Error),
+    #[error("could not parse answers: {0}")]
+    Toml(#[from] toml::de::Error),
+    #[error("{0} is not a day, use [day1] to [day25]")]
+    InvalidDay(String),
+    #[error("{0} is not a part, use part1 or part2")]
+    InvalidPart(String),
+    #[error("answer for {0}.{1} must be a string or an integer")]
+    InvalidAnswer(String, String),
+}
+
+/// The expected answer for each day and part.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Answers(BTreeMap<(u8, u8), String>);
+
+impl Answers {
+    /// ```
+    /// # use advent_of_code_2022::verify::Answers;
+    /// let answers = Answers::parse("\
+    /// [day5]
+    /// part1 = \"CMZ\"
+    /// part2 = 12
+    /// ").unwrap();
+    /// assert_eq!(answers.get(5, 1), Some("CMZ"));
+    /// assert_eq!(answers.get(5, 2), Some("12"));
+    /// assert_eq!(answers.get(6, 1), None);
+    /// ```
+    pub fn parse(text: &str) -> Result<Answers, ManifestError> {
+        let table: BTreeMap<String, BTreeMap<String, toml::Value>> =
+            toml::from_str(text)?;
+        let mut answers = BTreeMap::new();
+        for (day, parts) in table {
+            let number =
+                day.strip_prefix("day")
+                    .and_then(|x| x.parse().ok())
+                    .ok_or_else(|| ManifestError::InvalidDay(day.clone()))?;
+            for (part, value) in parts {
+                let part_number = match part.as_str() {
+                    "part1" => 1,
+                    "part2" => 2,
+                    _ => return Err(ManifestError::InvalidPart(part)),
+                };
+                let expected = match value {
+                    toml::Value::String(x) => x,
+                    toml::Value::Integer(x) => x.to_string(),
+                    _ => return Err(ManifestError::InvalidAnswer(day, part)),
+                };
+                answers.insert((number, part_number), expected);
+            }
+        }
+        Ok(Answers(answers))
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Answers, ManifestError> {
+        Answers::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Option<&str> {
+        self.0.get(&(day, part)).map(String::as_str)
+    }
+}
+
+#[derive(Debug)]
+pub enum Outcome {
+    Pass,
+    Fail { expected: String, actual: String },
+    Missing { actual: String },
+    Error(AocError),
+}
+
+#[derive(Debug)]
+pub struct Check {
+    pub day: u8,
+    pub part: u8,
+    pub outcome: Outcome,
+}
+
+impl Check {
+    /// Compares a solution's result with the expected answer, if there is
+    /// one.
+    pub fn new(
+        day: u8,
+        part: u8,
+        result: Result<Answer, AocError>,
+        expected: Option<&str>,
+    ) -> Check {
+        let outcome = match (result, expected) {
+            (Err(err), _) => Outcome::Error(err),
+            (Ok(answer), None) => Outcome::Missing {
+                actual: answer.to_string(),
+            },
+            (Ok(answer), Some(expected)) if answer.to_string() == expected => {
+                Outcome::Pass
+            }
+            (Ok(answer), Some(expected)) => Outcome::Fail {
+                expected: expected.to_string(),
+                actual: answer.to_string(),
+            },
+        };
+        Check { day, part, outcome }
+    }
+
+    /// Whether this check should fail a verification run. Missing answers are
+    /// reported but don't count as failures.
+    pub fn failed(&self) -> bool {
+        matches!(self.outcome, Outcome::Fail { .. } | Outcome::Error(_))
+    }
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Day {} Puzzle {} ", self.day, self.part)?;
+        match &self.outcome {
+            Outcome::Pass => write!(f, "pass"),
+            Outcome::Fail { expected, actual } => {
+                write!(f, "FAIL\n{}", diff(expected, actual))
+            }
+            Outcome::Missing { actual } if actual.contains('\n') => {
+                write!(f, "missing, got:\n{actual}")
+            }
+            Outcome::Missing { actual } => write!(f, "missing, got {actual}"),
+            Outcome::Error(err) => write!(f, "error: {err}"),
+        }
+    }
+}
+
+/// Shows both answers line by line, marking lines only in the expected answer
+/// with `-` and lines only in the actual answer with `+`.
+///
+/// ```
+/// # use advent_of_code_2022::verify::diff;
+/// assert_eq!(diff("CMZ", "MCD"), "- CMZ\n+ MCD");
+/// assert_eq!(diff("#.\n.#", "#.\n##"), "  #.\n- .#\n+ ##");
+/// ```
+pub fn diff(expected: &str, actual: &str) -> String {
+    expected
+        .lines()
+        .zip_longest(actual.lines())
+        .map(|pair| match pair {
+            EitherOrBoth::Both(e, a) if e == a => format!("  {e}"),
+            EitherOrBoth::Both(e, a) => format!("- {e}\n+ {a}"),
+            EitherOrBoth::Left(e) => format!("- {e}"),
+            EitherOrBoth::Right(a) => format!("+ {a}"),
+        })
+        .join("\n")
+}
+
+/// Runs the selected parts of a day against `input` and checks each result
+/// against the manifest.
+pub fn verify_day(
+    day: &Day,
+    input: &str,
+    parts: &[u8],
+    answers: &Answers,
+) -> Vec<Check> {
+    parts
+        .iter()
+        .map(|&part| {
+            let expected = answers.get(day.number, part);
+            Check::new(day.number, part, day.run(input, part), expected)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::verify::{self, Answers};
+    use crate::DAYS;
+    use std::fs;
+
+    #[test]
+    fn checked_in_answers_pass() {
+        let answers = Answers::load(verify::DEFAULT_ANSWERS).unwrap();
+        for day in &DAYS {
+            let input = fs::read_to_string(day.default_input()).unwrap();
+            for check in verify::verify_day(day, &input, &[1, 2], &answers) {
+                assert!(
+                    matches!(check.outcome, verify::Outcome::Pass),
+                    "{check}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn cannot_parse_invalid_manifest() {
+        assert!(matches!(
+            Answers::parse("[day1]\npart3 = 1"),
+            Err(verify::ManifestError::InvalidPart(_))
+        ));
+        assert!(matches!(
+            Answers::parse("[dayone]\npart1 = 1"),
+            Err(verify::ManifestError::InvalidDay(_))
+        ));
+    }
+}